@@ -0,0 +1,136 @@
+use serde::{Serialize, Deserialize};
+
+use crate::inventory::ItemStack;
+use crate::property::*;
+use crate::save::*;
+
+const POWER_SHARD_CLASS: &str = "Desc_CrystalShard_C";
+const SOMERSLOOP_CLASS: &str = "Desc_WAT1_C";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ManufacturerKind {
+  Constructor,
+  Assembler,
+  Manufacturer,
+  Refinery,
+  Blender,
+  Packager,
+  ParticleAccelerator,
+  Converter,
+  QuantumEncoder,
+  Smelter,
+  Foundry,
+}
+
+impl ManufacturerKind {
+  pub fn from_class_name(class_name: &str) -> Option<ManufacturerKind> {
+    match class_name {
+      "Build_ConstructorMk1_C" => Some(ManufacturerKind::Constructor),
+      "Build_AssemblerMk1_C" => Some(ManufacturerKind::Assembler),
+      "Build_ManufacturerMk1_C" => Some(ManufacturerKind::Manufacturer),
+      "Build_OilRefinery_C" => Some(ManufacturerKind::Refinery),
+      "Build_Blender_C" => Some(ManufacturerKind::Blender),
+      "Build_Packager_C" => Some(ManufacturerKind::Packager),
+      "Build_HadronCollider_C" => Some(ManufacturerKind::ParticleAccelerator),
+      "Build_Converter_C" => Some(ManufacturerKind::Converter),
+      "Build_QuantumEncoder_C" => Some(ManufacturerKind::QuantumEncoder),
+      "Build_SmelterMk1_C" => Some(ManufacturerKind::Smelter),
+      "Build_FoundryMk1_C" => Some(ManufacturerKind::Foundry),
+      _ => None,
+    }
+  }
+}
+
+/// A production building decoded from its actor and components. Properties
+/// still at their default value aren't saved by the game, so missing ones
+/// read as their defaults (e.g. 100% clock speed)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Manufacturer {
+  pub instance_name: String,
+  pub kind: ManufacturerKind,
+  pub position: Vector<f32>,
+  /// Path of the recipe, e.g.
+  /// `/Game/FactoryGame/Recipes/Constructor/Recipe_IronPlate.Recipe_IronPlate_C`
+  pub recipe: Option<String>,
+  /// Clock speed where 1.0 is 100%
+  pub current_potential: f32,
+  pub pending_potential: f32,
+  /// Output multiplier from somersloops where 1.0 is no boost
+  pub current_production_boost: f32,
+  pub pending_production_boost: f32,
+  pub power_shards: i32,
+  pub somersloops: i32,
+  /// Share of the last measurement period spent producing, from 0.0 to 1.0
+  pub productivity: f32,
+  pub manufacturing_progress: f32,
+  /// Whether the building was put on standby by the player
+  pub is_production_paused: bool,
+  pub input_inventory: Vec<ItemStack>,
+  pub output_inventory: Vec<ItemStack>,
+}
+
+impl Manufacturer {
+  /// Multiplier of the recipe's base rate for ingredients, which only the
+  /// clock speed changes
+  pub fn input_rate_multiplier(&self) -> f32 {
+    self.current_potential
+  }
+
+  /// Multiplier of the recipe's base rate for products, which somersloops
+  /// boost on top of the clock speed
+  pub fn output_rate_multiplier(&self) -> f32 {
+    self.current_potential * self.current_production_boost
+  }
+}
+
+impl Save {
+  /// Returns every production building in the save
+  pub fn manufacturers(&self) -> Vec<Manufacturer> {
+    let index = self.index();
+    self.iter_objects()
+      .filter_map(|(object_header, object)| match object_header {
+        ObjectHeader::Actor(a) => Some((a, object)),
+        ObjectHeader::Component(_) => None,
+      })
+      .filter_map(|(actor, object)| {
+        let kind = ManufacturerKind::from_class_name(class_name(&actor.type_path))?;
+        Some(Self::read_manufacturer(&index, actor, object, kind))
+      })
+      .collect()
+  }
+
+  fn read_manufacturer(index: &SaveIndex, actor: &ActorHeader, object: &Object, kind: ManufacturerKind) -> Manufacturer {
+    let properties = object.get_properties();
+
+    let inventory = |name: &str| property_reference(properties, name)
+      .map(|component| index.inventory_stacks(component))
+      .unwrap_or_default();
+
+    let potential_inventory = inventory("mInventoryPotential");
+    let count_of = |class: &str| potential_inventory.iter()
+      .filter(|s| s.class_name() == class)
+      .map(|s| s.count)
+      .sum();
+
+    let measurement = property_f32(properties, "mLastProductivityMeasurementDuration").unwrap_or(0.0);
+    let produce = property_f32(properties, "mLastProductivityMeasurementProduceDuration").unwrap_or(0.0);
+
+    Manufacturer {
+      instance_name: actor.instance_name.clone(),
+      kind,
+      position: actor.position.clone(),
+      recipe: property_reference(properties, "mCurrentRecipe").cloned(),
+      current_potential: property_f32(properties, "mCurrentPotential").unwrap_or(1.0),
+      pending_potential: property_f32(properties, "mPendingPotential").unwrap_or(1.0),
+      current_production_boost: property_f32(properties, "mCurrentProductionBoost").unwrap_or(1.0),
+      pending_production_boost: property_f32(properties, "mPendingProductionBoost").unwrap_or(1.0),
+      power_shards: count_of(POWER_SHARD_CLASS),
+      somersloops: count_of(SOMERSLOOP_CLASS),
+      productivity: if measurement > 0.0 { (produce / measurement).clamp(0.0, 1.0) } else { 0.0 },
+      manufacturing_progress: property_f32(properties, "mCurrentManufacturingProgress").unwrap_or(0.0),
+      is_production_paused: property_bool(properties, "mIsProductionPaused").unwrap_or(false),
+      input_inventory: inventory("mInputInventory"),
+      output_inventory: inventory("mOutputInventory"),
+    }
+  }
+}
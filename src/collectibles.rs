@@ -0,0 +1,264 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Serialize, Deserialize};
+
+use crate::property::*;
+use crate::save::*;
+use crate::Result;
+
+/// Totals of each kind of world-placed collectible on the default map, with
+/// an `items` list of their positions. Crash sites are saved as actors, so
+/// `CollectibleCatalogue::with_drop_pods` can list them from any save
+const EMBEDDED_CATALOGUE: &str = include_str!("data/collectibles.json");
+
+const DROP_POD_CLASS: &str = "BP_DropPod_C";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CollectibleKind {
+  Somersloop,
+  MercerSphere,
+  BlueSlug,
+  YellowSlug,
+  PurpleSlug,
+  DropPod,
+  HardDrive,
+  Foliage,
+  Other,
+}
+
+impl CollectibleKind {
+  /// Classifies a collectable by the class in its path name, e.g.
+  /// `Persistent_Level:PersistentLevel.BP_WAT1_C_12`
+  pub fn from_path_name(path_name: &str) -> CollectibleKind {
+    let name = path_name.rsplit('.').next().unwrap_or_default();
+    if name.starts_with("BP_WAT1") {
+      CollectibleKind::Somersloop
+    } else if name.starts_with("BP_WAT2") {
+      CollectibleKind::MercerSphere
+    } else if name.starts_with("BP_Crystal_mk2") {
+      CollectibleKind::YellowSlug
+    } else if name.starts_with("BP_Crystal_mk3") {
+      CollectibleKind::PurpleSlug
+    } else if name.starts_with("BP_Crystal") {
+      CollectibleKind::BlueSlug
+    } else if name.starts_with("BP_DropPod") {
+      CollectibleKind::DropPod
+    } else if name.contains("HardDrive") {
+      CollectibleKind::HardDrive
+    } else if name.contains("Foliage") || name.starts_with("BP_Shroom") || name.starts_with("BP_Nut") || name.starts_with("BP_Berry") {
+      CollectibleKind::Foliage
+    } else {
+      CollectibleKind::Other
+    }
+  }
+}
+
+/// What a crash site needs before it can be opened
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DropPodRepair {
+  /// Descriptor path of the part to hand in, e.g.
+  /// `.../Desc_Motor.Desc_Motor_C`
+  pub item: Option<String>,
+  pub amount: i32,
+  /// Power in MW to connect to the pod
+  pub power_consumption: f32,
+}
+
+impl DropPodRepair {
+  /// Reads the `mUnlockCost` struct of a drop pod: an `ItemCost` item
+  /// amount and a `PowerConsumption`
+  fn from_properties(properties: &[Property]) -> Option<DropPodRepair> {
+    let Some(PropertyValue::Struct((_, StructPropertyValue::Properties(cost)))) = find_property(properties, "mUnlockCost").map(|p| &p.value) else {
+      return None;
+    };
+
+    let mut repair = DropPodRepair::default();
+    if let Some(PropertyValue::Struct((_, StructPropertyValue::Properties(item_cost)))) = find_property(cost, "ItemCost").map(|p| &p.value) {
+      if let Some(PropertyValue::Object(o)) = find_property(item_cost, "ItemClass").map(|p| &p.value) {
+        repair.item = (!o.path_name.is_empty()).then(|| o.path_name.clone());
+      }
+      if let Some(PropertyValue::Int(amount)) = find_property(item_cost, "Amount").map(|p| &p.value) {
+        repair.amount = *amount;
+      }
+    }
+    if let Some(PropertyValue::Float(power)) = find_property(cost, "PowerConsumption").map(|p| &p.value) {
+      repair.power_consumption = *power;
+    }
+    Some(repair)
+  }
+
+  pub fn needs_power(&self) -> bool {
+    self.power_consumption > 0.0
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CatalogueItem {
+  pub path_name: String,
+  pub kind: CollectibleKind,
+  pub position: Vector<f32>,
+  /// Repair cost of a crash site
+  #[serde(default)]
+  pub repair: Option<DropPodRepair>,
+}
+
+/// The collectibles placed in the world, to compare a save's collected
+/// items against
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CollectibleCatalogue {
+  pub totals: BTreeMap<CollectibleKind, usize>,
+  #[serde(default)]
+  pub items: Vec<CatalogueItem>,
+}
+
+impl CollectibleCatalogue {
+  pub fn embedded() -> CollectibleCatalogue {
+    CollectibleCatalogue::from_json(EMBEDDED_CATALOGUE).unwrap_or_default()
+  }
+
+  /// Adds the crash sites of a save which aren't catalogued yet, with their
+  /// saved position and repair cost
+  pub fn with_drop_pods(mut self, collectibles: &Collectibles) -> CollectibleCatalogue {
+    let known: HashSet<String> = self.items.iter()
+      .map(|item| item.path_name.rsplit('.').next().unwrap_or_default().to_string())
+      .collect();
+    for pod in &collectibles.drop_pods {
+      if known.contains(pod.instance_name.rsplit('.').next().unwrap_or_default()) {
+        continue;
+      }
+      self.items.push(CatalogueItem {
+        path_name: pod.instance_name.clone(),
+        kind: CollectibleKind::DropPod,
+        position: pod.position.clone(),
+        repair: pod.repair.clone(),
+      });
+    }
+    self
+  }
+
+  /// Reads a catalogue of the same shape as the embedded one. Totals of
+  /// kinds missing from `totals` are counted from `items`
+  pub fn from_json(json: &str) -> Result<CollectibleCatalogue> {
+    let mut catalogue: CollectibleCatalogue = serde_json::from_str(json)?;
+    let mut counts: BTreeMap<CollectibleKind, usize> = BTreeMap::new();
+    for item in &catalogue.items {
+      *counts.entry(item.kind).or_default() += 1;
+    }
+    for (kind, count) in counts {
+      catalogue.totals.entry(kind).or_insert(count);
+    }
+    Ok(catalogue)
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DropPod {
+  pub instance_name: String,
+  pub position: Vector<f32>,
+  pub is_opened: bool,
+  /// Whether the part and power asked for have been provided
+  pub is_repaired: bool,
+  /// Repair cost saved with the pod. Saves which leave it to the map don't
+  /// have one, see `DropPod::repair_cost`
+  pub repair: Option<DropPodRepair>,
+}
+
+impl DropPod {
+  /// The repair cost saved with the pod, or else the catalogued one
+  pub fn repair_cost<'a>(&'a self, catalogue: &'a CollectibleCatalogue) -> Option<&'a DropPodRepair> {
+    self.repair.as_ref().or_else(|| {
+      let name = self.instance_name.rsplit('.').next().unwrap_or_default();
+      catalogue.items.iter()
+        .find(|item| item.kind == CollectibleKind::DropPod && item.path_name.rsplit('.').next().unwrap_or_default() == name)
+        .and_then(|item| item.repair.as_ref())
+    })
+  }
+}
+
+/// What's been collected in a save. Picked up items only remain in the save
+/// as references in the levels' collectables, while crash sites are saved
+/// as actors whether or not they were opened
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Collectibles {
+  /// Path names of collected items by kind
+  pub collected: BTreeMap<CollectibleKind, Vec<String>>,
+  pub drop_pods: Vec<DropPod>,
+}
+
+impl Collectibles {
+  pub fn collected_count(&self, kind: CollectibleKind) -> usize {
+    match kind {
+      CollectibleKind::DropPod if !self.drop_pods.is_empty() => self.drop_pods.iter().filter(|p| p.is_opened).count(),
+      _ => self.collected.get(&kind).map_or(0, |c| c.len()),
+    }
+  }
+
+  /// Number of each kind of collectible left to find
+  pub fn remaining(&self, catalogue: &CollectibleCatalogue) -> BTreeMap<CollectibleKind, usize> {
+    catalogue.totals.iter()
+      .map(|(kind, total)| (*kind, total.saturating_sub(self.collected_count(*kind))))
+      .collect()
+  }
+
+  /// Catalogued items which haven't been collected yet
+  pub fn remaining_items<'a>(&self, catalogue: &'a CollectibleCatalogue) -> Vec<&'a CatalogueItem> {
+    let collected: HashSet<&str> = self.collected.values()
+      .flatten()
+      .map(|p| p.rsplit('.').next().unwrap_or_default())
+      .chain(self.drop_pods.iter().filter(|p| p.is_opened).map(|p| p.instance_name.rsplit('.').next().unwrap_or_default()))
+      .collect();
+
+    catalogue.items.iter()
+      .filter(|item| !collected.contains(item.path_name.rsplit('.').next().unwrap_or_default()))
+      .collect()
+  }
+
+  /// Catalogued items which haven't been collected yet within `radius` of a
+  /// point, closest first, with their distance
+  pub fn remaining_near<'a>(&self, catalogue: &'a CollectibleCatalogue, point: &Vector<f32>, radius: f32) -> Vec<(&'a CatalogueItem, f32)> {
+    let mut items: Vec<(&CatalogueItem, f32)> = self.remaining_items(catalogue).into_iter()
+      .map(|item| {
+        let (dx, dy, dz) = (item.position.x - point.x, item.position.y - point.y, item.position.z - point.z);
+        (item, (dx * dx + dy * dy + dz * dz).sqrt())
+      })
+      .filter(|(_, distance)| *distance <= radius)
+      .collect();
+    items.sort_by(|a, b| a.1.total_cmp(&b.1));
+    items
+  }
+}
+
+impl Save {
+  pub fn collectibles(&self) -> Collectibles {
+    let mut collectibles = Collectibles::default();
+
+    let mut seen: HashSet<&String> = HashSet::new();
+    let references = self.levels.iter().flat_map(|level| level.collectables.iter().chain(&level.second_collectables));
+    for reference in references {
+      if reference.path_name.is_empty() || !seen.insert(&reference.path_name) {
+        continue;
+      }
+      collectibles.collected.entry(CollectibleKind::from_path_name(&reference.path_name))
+        .or_default()
+        .push(reference.path_name.clone());
+    }
+
+    for (object_header, object) in self.iter_objects() {
+      if let ObjectHeader::Actor(actor) = object_header {
+        if class_name(&actor.type_path) == DROP_POD_CLASS {
+          let properties = object.get_properties();
+          let is_set = |name: &str| property_bool(properties, name).unwrap_or(false);
+          collectibles.drop_pods.push(DropPod {
+            instance_name: actor.instance_name.clone(),
+            position: actor.position.clone(),
+            is_opened: is_set("mHasBeenOpened"),
+            is_repaired: is_set("mHasBeenRepaired"),
+            repair: DropPodRepair::from_properties(properties),
+          });
+        }
+      }
+    }
+
+    collectibles
+  }
+}
@@ -0,0 +1,275 @@
+use serde::{Serialize, Deserialize};
+
+use crate::errors::ParseError;
+use crate::property::*;
+use crate::region::Region;
+use crate::save::*;
+use crate::Result;
+
+const GAME_STATE_CLASS: &str = "BP_GameState_C";
+const CUSTOMIZATION_PROPERTY: &str = "mCustomizationData";
+const CUSTOMIZATION_STRUCT: &str = "FactoryCustomizationData";
+const COLOR_SLOT_STRUCT: &str = "FactoryCustomizationColorSlot";
+const BUILDABLE_PATH: &str = "/Buildable/";
+
+/// The swatch which makes a buildable use its override colours instead of
+/// one of the colour slots
+pub const CUSTOM_SWATCH: &str = "/Game/FactoryGame/Buildable/-Shared/Customization/Swatches/SwatchDesc_Custom.SwatchDesc_Custom_C";
+
+/// A pair of colours with an optional paint finish, as used by both the
+/// player-defined colour slots and a buildable's override colours
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ColorSlot {
+  pub primary_color: Color<f32>,
+  pub secondary_color: Color<f32>,
+  pub paint_finish: Option<String>,
+}
+
+impl ColorSlot {
+  /// Reads a slot from the properties of a `FactoryCustomizationColorSlot`
+  /// struct
+  pub fn from_properties(properties: &[Property]) -> ColorSlot {
+    ColorSlot {
+      primary_color: linear_color(properties, "PrimaryColor").unwrap_or_default(),
+      secondary_color: linear_color(properties, "SecondaryColor").unwrap_or_default(),
+      paint_finish: property_reference(properties, "PaintFinish").cloned(),
+    }
+  }
+
+  /// Updates the properties of a `FactoryCustomizationColorSlot` struct,
+  /// leaving any others as they are
+  pub fn apply_to(&self, properties: &mut Vec<Property>) {
+    set_property_value(properties, "PrimaryColor", PropertyValue::Struct(("LinearColor".to_string(), StructPropertyValue::LinearColor(self.primary_color.clone()))));
+    set_property_value(properties, "SecondaryColor", PropertyValue::Struct(("LinearColor".to_string(), StructPropertyValue::LinearColor(self.secondary_color.clone()))));
+    match &self.paint_finish {
+      Some(paint_finish) => set_property_value(properties, "PaintFinish", PropertyValue::Object(asset_reference(paint_finish))),
+      None => properties.retain(|p| p.name != "PaintFinish"),
+    }
+  }
+}
+
+/// The colour slots players pick from in the customizer
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ColorSlots {
+  pub slots: Vec<ColorSlot>,
+}
+
+fn linear_color(properties: &[Property], name: &str) -> Option<Color<f32>> {
+  match find_property(properties, name).map(|p| &p.value) {
+    Some(PropertyValue::Struct((_, StructPropertyValue::LinearColor(c)))) => Some(c.clone()),
+    _ => None,
+  }
+}
+
+fn linear_colors(properties: &[Property], name: &str) -> Vec<Color<f32>> {
+  match find_property(properties, name).map(|p| &p.value) {
+    Some(PropertyValue::Array(a)) => a.elements.iter()
+      .filter_map(|e| match e {
+        ArrayPropertyValue::Struct(ArrayPropertyStructValue::LinearColor(c)) => Some(c.clone()),
+        _ => None,
+      })
+      .collect(),
+    _ => vec![],
+  }
+}
+
+fn asset_reference(path_name: &str) -> ObjectReference {
+  ObjectReference {
+    level_name: String::new(),
+    path_name: path_name.to_string(),
+    level_is_map: false,
+  }
+}
+
+impl Customization {
+  /// Reads the properties of a `FactoryCustomizationData` struct. Fields the
+  /// save leaves out are empty references and black
+  pub fn from_properties(properties: &[Property]) -> Customization {
+    let asset = |name: &str| asset_reference(property_reference(properties, name).map_or("", |r| r.as_str()));
+    let colors = match find_property(properties, "OverrideColorData").map(|p| &p.value) {
+      Some(PropertyValue::Struct((_, StructPropertyValue::Properties(p)))) => ColorSlot::from_properties(p),
+      _ => ColorSlot::default(),
+    };
+
+    Customization {
+      swatch: asset("SwatchDesc"),
+      material: asset("MaterialDesc"),
+      pattern: asset("PatternDesc"),
+      skin: asset("SkinDesc"),
+      primary_color: colors.primary_color,
+      secondary_color: colors.secondary_color,
+      paint_finish: asset_reference(&colors.paint_finish.unwrap_or_default()),
+      pattern_rotation: match find_property(properties, "PatternRotation").map(|p| &p.value) {
+        Some(PropertyValue::Byte(b)) => b.byte_value.unwrap_or(0),
+        _ => 0,
+      },
+    }
+  }
+
+  /// The override colours, which only apply with the custom swatch
+  pub fn colors(&self) -> ColorSlot {
+    ColorSlot {
+      primary_color: self.primary_color.clone(),
+      secondary_color: self.secondary_color.clone(),
+      paint_finish: Some(self.paint_finish.path_name.clone()).filter(|p| !p.is_empty()),
+    }
+  }
+
+  /// Sets the swatch and, when given, the override colours
+  pub fn recolor(&mut self, swatch: &str, colors: Option<&ColorSlot>) {
+    self.swatch = asset_reference(swatch);
+    if let Some(colors) = colors {
+      self.primary_color = colors.primary_color.clone();
+      self.secondary_color = colors.secondary_color.clone();
+      self.paint_finish = asset_reference(colors.paint_finish.as_deref().unwrap_or_default());
+    }
+  }
+}
+
+/// Sets the swatch and override colours of a `FactoryCustomizationData`
+/// struct's properties, keeping its material, pattern and skin
+fn recolor_properties(properties: &mut Vec<Property>, swatch: &str, colors: Option<&ColorSlot>) {
+  set_property_value(properties, "SwatchDesc", PropertyValue::Object(asset_reference(swatch)));
+
+  let Some(colors) = colors else {
+    return;
+  };
+  match find_property_mut(properties, "OverrideColorData").map(|p| &mut p.value) {
+    Some(PropertyValue::Struct((_, StructPropertyValue::Properties(p)))) => colors.apply_to(p),
+    _ => {
+      let mut p = vec![];
+      colors.apply_to(&mut p);
+      set_property_value(properties, "OverrideColorData", PropertyValue::Struct((COLOR_SLOT_STRUCT.to_string(), StructPropertyValue::Properties(p))));
+    },
+  }
+}
+
+/// Recolors an object's customization data, adding it if the object has
+/// none yet
+fn recolor_object(object: &mut Object, swatch: &str, colors: Option<&ColorSlot>) {
+  let properties = object.get_properties_mut();
+  match find_property_mut(properties, CUSTOMIZATION_PROPERTY).map(|p| &mut p.value) {
+    Some(PropertyValue::Struct((_, StructPropertyValue::Properties(p)))) => recolor_properties(p, swatch, colors),
+    _ => {
+      let mut p = vec![];
+      recolor_properties(&mut p, swatch, colors);
+      set_property_value(properties, CUSTOMIZATION_PROPERTY, PropertyValue::Struct((CUSTOMIZATION_STRUCT.to_string(), StructPropertyValue::Properties(p))));
+    },
+  }
+}
+
+impl Save {
+  /// Returns the customization of a buildable, if it has been customized
+  pub fn customization(&self, instance_name: &str) -> Option<Customization> {
+    let (_, object) = self.find_object(instance_name)?;
+    match find_property(object.get_properties(), CUSTOMIZATION_PROPERTY).map(|p| &p.value) {
+      Some(PropertyValue::Struct((_, StructPropertyValue::Properties(p)))) => Some(Customization::from_properties(p)),
+      _ => None,
+    }
+  }
+
+  /// Returns the player-defined colour slots, from either the 1.0 list of
+  /// slots or the older separate lists of primary and secondary colours
+  pub fn color_slots(&self) -> ColorSlots {
+    let Some((_, game_state)) = self.iter_objects().find(|(object_header, _)| object_header.get_class_name() == GAME_STATE_CLASS) else {
+      return ColorSlots::default();
+    };
+    let properties = game_state.get_properties();
+
+    if let Some(PropertyValue::Array(a)) = find_property(properties, "mColorSlots_Data").map(|p| &p.value) {
+      return ColorSlots {
+        slots: a.elements.iter()
+          .filter_map(|e| match e {
+            ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(p)) => Some(ColorSlot::from_properties(p)),
+            _ => None,
+          })
+          .collect(),
+      };
+    }
+
+    let primary = linear_colors(properties, "mColorSlotsPrimary_Linear");
+    let secondary = linear_colors(properties, "mColorSlotsSecondary_Linear");
+    ColorSlots {
+      slots: primary.into_iter()
+        .zip(secondary)
+        .map(|(primary_color, secondary_color)| ColorSlot {
+          primary_color,
+          secondary_color,
+          paint_finish: None,
+        })
+        .collect(),
+    }
+  }
+
+  /// Replaces an existing colour slot. Returns whether the slot exists
+  pub fn set_color_slot(&mut self, index: usize, slot: &ColorSlot) -> Result<bool> {
+    let game_state = self.levels.iter_mut()
+      .flat_map(|level| level.object_headers.iter().zip(level.objects.iter_mut()))
+      .find(|(object_header, _)| object_header.get_class_name() == GAME_STATE_CLASS);
+    let Some((_, game_state)) = game_state else {
+      return Err(ParseError::MissingObject(GAME_STATE_CLASS.to_string()));
+    };
+    let properties = game_state.get_properties_mut();
+
+    if let Some(PropertyValue::Array(a)) = find_property_mut(properties, "mColorSlots_Data").map(|p| &mut p.value) {
+      return Ok(match a.elements.get_mut(index) {
+        Some(ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(p))) => {
+          slot.apply_to(p);
+          true
+        },
+        _ => false,
+      });
+    }
+
+    let mut found = false;
+    for (name, color) in [("mColorSlotsPrimary_Linear", &slot.primary_color), ("mColorSlotsSecondary_Linear", &slot.secondary_color)] {
+      if let Some(PropertyValue::Array(a)) = find_property_mut(properties, name).map(|p| &mut p.value) {
+        if let Some(ArrayPropertyValue::Struct(ArrayPropertyStructValue::LinearColor(c))) = a.elements.get_mut(index) {
+          *c = color.clone();
+          found = true;
+        }
+      }
+    }
+    Ok(found)
+  }
+
+  /// Sets the swatch and, when given, the override colours of every
+  /// buildable (lightweight ones included) for which `predicate` returns
+  /// true given its class name and position. Returns how many were changed
+  fn recolor_where<F: Fn(&str, &Vector<f32>) -> bool>(&mut self, swatch: &str, colors: Option<&ColorSlot>, predicate: F) -> usize {
+    let mut count = 0;
+
+    for level in &mut self.levels {
+      for (object_header, object) in level.object_headers.iter().zip(level.objects.iter_mut()) {
+        let ObjectHeader::Actor(actor) = object_header else {
+          continue;
+        };
+        if actor.type_path.contains(BUILDABLE_PATH) && predicate(class_name(&actor.type_path), &actor.position) {
+          recolor_object(object, swatch, colors);
+          count += 1;
+        }
+      }
+    }
+
+    if let Some(lightweight) = self.lightweight_buildables_mut() {
+      for (class, instance) in lightweight.iter_mut() {
+        if predicate(class_name(class), &instance.transform.position()) {
+          instance.customization.recolor(swatch, colors);
+          count += 1;
+        }
+      }
+    }
+
+    count
+  }
+
+  /// Recolors every buildable of a class, e.g. `Build_Wall_8x4_01_C`
+  pub fn recolor_class(&mut self, class: &str, swatch: &str, colors: Option<&ColorSlot>) -> usize {
+    self.recolor_where(swatch, colors, |c, _| c == class)
+  }
+
+  /// Recolors every buildable within a region
+  pub fn recolor_region(&mut self, region: &Region, swatch: &str, colors: Option<&ColorSlot>) -> usize {
+    self.recolor_where(swatch, colors, |_, position| region.contains(position))
+  }
+}
@@ -0,0 +1,317 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use crate::property::*;
+use crate::save::*;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ChangeKind {
+  Added,
+  Removed,
+  Modified,
+}
+
+/// A single changed value, located by a path such as
+/// `mInventoryStacks[0].Item` (or `position` for actor header fields)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Change {
+  pub path: String,
+  pub kind: ChangeKind,
+  pub before: Option<Value>,
+  pub after: Option<Value>,
+}
+
+impl Change {
+  fn added(path: String, after: Value) -> Change {
+    Change { path, kind: ChangeKind::Added, before: None, after: Some(after) }
+  }
+
+  fn removed(path: String, before: Value) -> Change {
+    Change { path, kind: ChangeKind::Removed, before: Some(before), after: None }
+  }
+
+  fn modified(path: String, before: Value, after: Value) -> Change {
+    Change { path, kind: ChangeKind::Modified, before: Some(before), after: Some(after) }
+  }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ObjectDiff {
+  pub instance_name: String,
+  pub type_path: String,
+  pub changes: Vec<Change>,
+}
+
+/// Objects are identified by their instance name so an object which was
+/// moved to a different level is reported as modified rather than as removed
+/// and added
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SaveDiff {
+  pub header: Vec<Change>,
+  pub added: Vec<(String, String)>,
+  pub removed: Vec<(String, String)>,
+  pub modified: Vec<ObjectDiff>,
+  pub collectables_added: Vec<String>,
+  pub collectables_removed: Vec<String>,
+}
+
+impl SaveDiff {
+  pub fn is_empty(&self) -> bool {
+    self.header.is_empty() &&
+    self.added.is_empty() &&
+    self.removed.is_empty() &&
+    self.modified.is_empty() &&
+    self.collectables_added.is_empty() &&
+    self.collectables_removed.is_empty()
+  }
+
+  pub fn to_json(&self) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(self)
+  }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Value {
+  serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Compares two JSON objects field by field, e.g. two headers
+fn diff_fields(prefix: &str, before: &Value, after: &Value, changes: &mut Vec<Change>) {
+  let (Value::Object(before), Value::Object(after)) = (before, after) else {
+    if before != after {
+      changes.push(Change::modified(prefix.to_string(), before.clone(), after.clone()));
+    }
+    return;
+  };
+
+  for (key, b) in before {
+    let path = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+    match after.get(key) {
+      Some(a) if a != b => changes.push(Change::modified(path, b.clone(), a.clone())),
+      Some(_) => {},
+      None => changes.push(Change::removed(path, b.clone())),
+    }
+  }
+  for (key, a) in after {
+    if !before.contains_key(key) {
+      let path = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+      changes.push(Change::added(path, a.clone()));
+    }
+  }
+}
+
+fn property_path(prefix: &str, property: &Property) -> String {
+  let name = if property.index == 0 {
+    property.name.clone()
+  } else {
+    format!("{}({})", property.name, property.index)
+  };
+  if prefix.is_empty() { name } else { format!("{prefix}.{name}") }
+}
+
+/// Compares two lists of properties, matching them up by name and index.
+/// Nested structs are compared property by property, sizes are ignored
+fn diff_properties(prefix: &str, before: &[Property], after: &[Property], changes: &mut Vec<Change>) {
+  let after_by_key: HashMap<(&str, i32), &Property> = after.iter()
+    .map(|p| ((p.name.as_str(), p.index), p))
+    .collect();
+  let before_by_key: HashMap<(&str, i32), &Property> = before.iter()
+    .map(|p| ((p.name.as_str(), p.index), p))
+    .collect();
+
+  for b in before {
+    let path = property_path(prefix, b);
+    match after_by_key.get(&(b.name.as_str(), b.index)) {
+      Some(a) => diff_property_values(&path, &b.value, &a.value, changes),
+      None => changes.push(Change::removed(path, to_value(&b.value))),
+    }
+  }
+  for a in after {
+    if !before_by_key.contains_key(&(a.name.as_str(), a.index)) {
+      changes.push(Change::added(property_path(prefix, a), to_value(&a.value)));
+    }
+  }
+}
+
+fn diff_property_values(path: &str, before: &PropertyValue, after: &PropertyValue, changes: &mut Vec<Change>) {
+  match (before, after) {
+    (PropertyValue::Struct((bt, StructPropertyValue::Properties(b))), PropertyValue::Struct((at, StructPropertyValue::Properties(a)))) if bt == at => {
+      diff_properties(path, b, a, changes);
+    },
+    (PropertyValue::Struct((bt, StructPropertyValue::InventoryItem(b))), PropertyValue::Struct((at, StructPropertyValue::InventoryItem(a)))) if bt == at => {
+      diff_fields(path, &to_value(&(&b.item_name, &b.object)), &to_value(&(&a.item_name, &a.object)), changes);
+      diff_properties(path, std::slice::from_ref(&b.property), std::slice::from_ref(&a.property), changes);
+    },
+    (PropertyValue::Array(b), PropertyValue::Array(a)) if b.r#type == a.r#type => {
+      for (i, (be, ae)) in b.elements.iter().zip(&a.elements).enumerate() {
+        let element_path = format!("{path}[{i}]");
+        match (be, ae) {
+          (ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(b)), ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(a))) => {
+            diff_properties(&element_path, b, a, changes);
+          },
+          _ => {
+            let (bv, av) = (to_value(be), to_value(ae));
+            if bv != av {
+              changes.push(Change::modified(element_path, bv, av));
+            }
+          },
+        }
+      }
+      for (i, be) in b.elements.iter().enumerate().skip(a.elements.len()) {
+        changes.push(Change::removed(format!("{path}[{i}]"), to_value(be)));
+      }
+      for (i, ae) in a.elements.iter().enumerate().skip(b.elements.len()) {
+        changes.push(Change::added(format!("{path}[{i}]"), to_value(ae)));
+      }
+    },
+    _ => {
+      let (bv, av) = (to_value(before), to_value(after));
+      if bv != av {
+        changes.push(Change::modified(path.to_string(), bv, av));
+      }
+    },
+  }
+}
+
+fn diff_objects(before: (&ObjectHeader, &Object), after: (&ObjectHeader, &Object)) -> Vec<Change> {
+  let mut changes: Vec<Change> = vec![];
+
+  match (before.0, after.0) {
+    (ObjectHeader::Actor(b), ObjectHeader::Actor(a)) => {
+      diff_fields("position", &to_value(&b.position), &to_value(&a.position), &mut changes);
+      diff_fields("rotation", &to_value(&b.rotation), &to_value(&a.rotation), &mut changes);
+      diff_fields("scale", &to_value(&b.scale), &to_value(&a.scale), &mut changes);
+    },
+    (b, a) => diff_fields("header", &to_value(b), &to_value(a), &mut changes),
+  }
+
+  if let (Object::Actor(b), Object::Actor(a)) = (before.1, after.1) {
+    let (bv, av) = (to_value(&b.components), to_value(&a.components));
+    if bv != av {
+      changes.push(Change::modified("components".to_string(), bv, av));
+    }
+  }
+
+  diff_properties("", before.1.get_properties(), after.1.get_properties(), &mut changes);
+
+  let (bv, av) = (to_value(&before.1.get_extra()), to_value(&after.1.get_extra()));
+  if bv != av {
+    changes.push(Change::modified("extra".to_string(), bv, av));
+  }
+
+  changes
+}
+
+fn collectable_names(save: &Save) -> BTreeSet<&String> {
+  save.levels.iter()
+    .flat_map(|level| level.collectables.iter().chain(&level.second_collectables))
+    .map(|c| &c.path_name)
+    .collect()
+}
+
+/// Compares two saves: their headers, which objects were added, removed or
+/// modified (down to individual nested properties) and which collectables
+/// were picked up or restored
+pub fn diff(before: &Save, after: &Save) -> SaveDiff {
+  let mut diff = SaveDiff::default();
+
+  diff_fields("", &to_value(&before.header), &to_value(&after.header), &mut diff.header);
+
+  let before_objects: HashMap<&String, (&ObjectHeader, &Object)> = before.iter_objects()
+    .map(|(h, o)| (h.get_instance_name(), (h, o)))
+    .collect();
+  let after_objects: HashMap<&String, (&ObjectHeader, &Object)> = after.iter_objects()
+    .map(|(h, o)| (h.get_instance_name(), (h, o)))
+    .collect();
+
+  for (object_header, object) in before.iter_objects() {
+    let name = object_header.get_instance_name();
+    match after_objects.get(name) {
+      Some(a) => {
+        let changes = diff_objects((object_header, object), *a);
+        if !changes.is_empty() {
+          diff.modified.push(ObjectDiff {
+            instance_name: name.clone(),
+            type_path: object_header.get_type_path().clone(),
+            changes,
+          });
+        }
+      },
+      None => diff.removed.push((name.clone(), object_header.get_type_path().clone())),
+    }
+  }
+  for (object_header, _) in after.iter_objects() {
+    if !before_objects.contains_key(object_header.get_instance_name()) {
+      diff.added.push((object_header.get_instance_name().clone(), object_header.get_type_path().clone()));
+    }
+  }
+
+  let before_collectables = collectable_names(before);
+  let after_collectables = collectable_names(after);
+  diff.collectables_added = after_collectables.difference(&before_collectables).map(|s| s.to_string()).collect();
+  diff.collectables_removed = before_collectables.difference(&after_collectables).map(|s| s.to_string()).collect();
+
+  diff
+}
+
+fn write_change(f: &mut fmt::Formatter<'_>, indent: &str, change: &Change) -> fmt::Result {
+  let value = |v: &Option<Value>| v.as_ref().map(|v| v.to_string()).unwrap_or_default();
+  match change.kind {
+    ChangeKind::Added => writeln!(f, "{indent}+ {}: {}", change.path, value(&change.after)),
+    ChangeKind::Removed => writeln!(f, "{indent}- {}: {}", change.path, value(&change.before)),
+    ChangeKind::Modified => writeln!(f, "{indent}~ {}: {} -> {}", change.path, value(&change.before), value(&change.after)),
+  }
+}
+
+impl fmt::Display for SaveDiff {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.is_empty() {
+      return writeln!(f, "No changes");
+    }
+
+    if !self.header.is_empty() {
+      writeln!(f, "Header:")?;
+      for change in &self.header {
+        write_change(f, "  ", change)?;
+      }
+    }
+
+    if !self.added.is_empty() {
+      writeln!(f, "Added objects ({}):", self.added.len())?;
+      for (name, type_path) in &self.added {
+        writeln!(f, "  + {name} ({type_path})")?;
+      }
+    }
+
+    if !self.removed.is_empty() {
+      writeln!(f, "Removed objects ({}):", self.removed.len())?;
+      for (name, type_path) in &self.removed {
+        writeln!(f, "  - {name} ({type_path})")?;
+      }
+    }
+
+    if !self.modified.is_empty() {
+      writeln!(f, "Modified objects ({}):", self.modified.len())?;
+      for object in &self.modified {
+        writeln!(f, "  ~ {} ({})", object.instance_name, object.type_path)?;
+        for change in &object.changes {
+          write_change(f, "      ", change)?;
+        }
+      }
+    }
+
+    if !self.collectables_added.is_empty() || !self.collectables_removed.is_empty() {
+      writeln!(f, "Collectables:")?;
+      for name in &self.collectables_added {
+        writeln!(f, "  + {name}")?;
+      }
+      for name in &self.collectables_removed {
+        writeln!(f, "  - {name}")?;
+      }
+    }
+
+    Ok(())
+  }
+}
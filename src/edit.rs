@@ -0,0 +1,265 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::errors::ParseError;
+use crate::property::*;
+use crate::save::*;
+use crate::visit::*;
+use crate::Result;
+
+/// Rewrites every reference whose path name is a key of `names` to point at
+/// the corresponding value instead
+#[derive(Debug, Default)]
+pub struct RemapReferences {
+  pub names: HashMap<String, String>,
+}
+
+impl VisitorMut for RemapReferences {
+  fn visit_object_reference_mut(&mut self, reference: &mut ObjectReference, _path: &mut VisitPath) {
+    if let Some(name) = self.names.get(&reference.path_name) {
+      reference.path_name = name.clone();
+    }
+  }
+}
+
+/// Clears every reference whose path name is in `names`, which is how the
+/// game represents a reference to nothing
+#[derive(Debug, Default)]
+pub struct NullReferences {
+  pub names: HashSet<String>,
+}
+
+impl VisitorMut for NullReferences {
+  fn visit_object_mut(&mut self, object: &mut Object, path: &mut VisitPath) {
+    // An actor's list of components drops removed components entirely
+    if let Object::Actor(a) = object {
+      a.components.retain(|c| !self.names.contains(&c.path_name));
+      a.num_components = a.components.len() as i32;
+    }
+    walk_object_mut(self, object, path)
+  }
+
+  fn visit_property_value_mut(&mut self, value: &mut PropertyValue, path: &mut VisitPath) {
+    // Lists of references (e.g. a power connection's wires) shrink instead
+    // of keeping an empty entry
+    if let PropertyValue::Array(a) = value {
+      a.elements.retain(|e| match e {
+        ArrayPropertyValue::Object(o) => !self.names.contains(&o.path_name),
+        _ => true,
+      });
+    }
+    walk_property_value_mut(self, value, path)
+  }
+
+  fn visit_object_reference_mut(&mut self, reference: &mut ObjectReference, _path: &mut VisitPath) {
+    if self.names.contains(&reference.path_name) {
+      *reference = ObjectReference::default();
+    }
+  }
+}
+
+/// Splits an instance name such as `Persistent_Level:PersistentLevel.Build_Wall_C_123`
+/// into its prefix (`...Build_Wall_C_`) and numeric suffix (`123`)
+fn split_instance_name(instance_name: &str) -> (&str, Option<u64>) {
+  match instance_name.rfind('_') {
+    Some(i) => match instance_name[i + 1..].parse::<u64>() {
+      Ok(n) => (&instance_name[..=i], Some(n)),
+      Err(_) => (instance_name, None),
+    },
+    None => (instance_name, None),
+  }
+}
+
+impl Save {
+  /// Returns an instance name based on the given one which no object in
+  /// this save uses yet, by replacing its numeric suffix
+  pub fn unique_instance_name(&self, instance_name: &str) -> String {
+    self.unique_instance_name_excluding(instance_name, &HashSet::new())
+  }
+
+  /// Same as above but also avoids the names in `reserved`, e.g. names
+  /// already handed out for objects which haven't been added yet
+  pub fn unique_instance_name_excluding(&self, instance_name: &str, reserved: &HashSet<String>) -> String {
+    let (prefix, _) = split_instance_name(instance_name);
+    let prefix = if prefix == instance_name {
+      format!("{instance_name}_")
+    } else {
+      prefix.to_string()
+    };
+
+    let mut max = 0;
+    let names = self.iter_objects().map(|(h, _)| h.get_instance_name()).chain(reserved.iter());
+    for name in names {
+      if let Some(rest) = name.strip_prefix(&prefix) {
+        if let Ok(n) = rest.parse::<u64>() {
+          max = max.max(n);
+        }
+      }
+    }
+
+    format!("{prefix}{}", max + 1)
+  }
+
+  /// Returns the instance names of an actor's components
+  pub fn component_names(&self, instance_name: &str) -> Result<Vec<String>> {
+    match self.find_object(instance_name) {
+      Some((_, Object::Actor(a))) => Ok(a.components.iter().map(|c| c.path_name.clone()).collect()),
+      Some(_) => Err(ParseError::NotAnActor(instance_name.to_string())),
+      None => Err(ParseError::MissingObject(instance_name.to_string())),
+    }
+  }
+
+  /// Removes objects by instance name and clears every reference to them
+  /// that's left in the save. Returns the names which were found and removed.
+  /// Lightweight buildables aren't objects of their own, so they're removed
+  /// with `LightweightBuildables::retain` instead
+  pub fn delete_objects(&mut self, instance_names: &HashSet<String>) -> Vec<String> {
+    let mut removed: Vec<String> = vec![];
+
+    for level in &mut self.levels {
+      let mut i = 0;
+      while i < level.object_headers.len() {
+        let name = level.object_headers[i].get_instance_name();
+        if instance_names.contains(name) {
+          removed.push(name.clone());
+          level.object_headers.remove(i);
+          if i < level.objects.len() {
+            level.objects.remove(i);
+          }
+        } else {
+          i += 1;
+        }
+      }
+    }
+
+    let mut visitor = NullReferences {
+      names: removed.iter().cloned().collect(),
+    };
+    visitor.visit_save_mut(self, &mut VisitPath::new());
+
+    removed
+  }
+
+  /// Removes an actor together with its components and clears every
+  /// reference to them. Returns the names of every removed object
+  pub fn delete_actor(&mut self, instance_name: &str) -> Result<Vec<String>> {
+    let mut names: HashSet<String> = self.component_names(instance_name)?.into_iter().collect();
+    names.insert(instance_name.to_string());
+    Ok(self.delete_objects(&names))
+  }
+
+  /// Copies an actor and its components into the same level under fresh
+  /// instance names. References between the copied objects are remapped to
+  /// the copies while references to anything else are kept. Returns the
+  /// instance name of the new actor. Lightweight buildables are copied with
+  /// `LightweightBuildables::add` instead
+  pub fn clone_actor(&mut self, instance_name: &str) -> Result<String> {
+    let (level_index, _) = self.position_of(instance_name)
+      .ok_or_else(|| ParseError::MissingObject(instance_name.to_string()))?;
+
+    let mut names: Vec<String> = vec![instance_name.to_string()];
+    names.extend(self.component_names(instance_name)?);
+
+    let new_name = self.unique_instance_name(instance_name);
+    let mut reserved: HashSet<String> = HashSet::from([new_name.clone()]);
+
+    // Component names are the actor name followed by the component name,
+    // e.g. `...Build_Wall_C_123.PowerConnection`, so only the prefix changes
+    let mut visitor = RemapReferences::default();
+    for name in &names {
+      let new = match name.strip_prefix(instance_name) {
+        Some(rest) => format!("{new_name}{rest}"),
+        None => self.unique_instance_name_excluding(name, &reserved),
+      };
+      reserved.insert(new.clone());
+      visitor.names.insert(name.clone(), new);
+    }
+
+    let mut copies: Vec<(ObjectHeader, Object)> = vec![];
+    for name in &names {
+      if let Some((object_header, object)) = self.find_object(name) {
+        copies.push((object_header.clone(), object.clone()));
+      }
+    }
+
+    let level = &mut self.levels[level_index];
+    for (mut object_header, mut object) in copies {
+      let name = object_header.get_instance_name().clone();
+      let mut path = VisitPath::new();
+      path.push(PathSegment::Object(name.clone()));
+      visitor.visit_object_mut(&mut object, &mut path);
+
+      object_header.set_instance_name(visitor.names[&name].clone());
+      if let ObjectHeader::Component(c) = &mut object_header {
+        if let Some(parent) = visitor.names.get(&c.parent_actor_name) {
+          c.parent_actor_name = parent.clone();
+        }
+      }
+
+      level.object_headers.push(object_header);
+      level.objects.push(object);
+    }
+
+    Ok(new_name)
+  }
+
+  fn find_actor_header_mut(&mut self, instance_name: &str) -> Result<&mut ActorHeader> {
+    match self.find_object_mut(instance_name) {
+      Some((ObjectHeader::Actor(a), _)) => Ok(a),
+      Some(_) => Err(ParseError::NotAnActor(instance_name.to_string())),
+      None => Err(ParseError::MissingObject(instance_name.to_string())),
+    }
+  }
+
+  /// Moves an actor by the given offset
+  pub fn translate_actor(&mut self, instance_name: &str, offset: &Vector<f32>) -> Result<()> {
+    let actor = self.find_actor_header_mut(instance_name)?;
+    actor.position.x += offset.x;
+    actor.position.y += offset.y;
+    actor.position.z += offset.z;
+    Ok(())
+  }
+
+  /// Rotates an actor in place by the given rotation
+  pub fn rotate_actor(&mut self, instance_name: &str, rotation: &Quaternion<f32>) -> Result<()> {
+    let actor = self.find_actor_header_mut(instance_name)?;
+    actor.rotation = rotation.multiply(&actor.rotation);
+    Ok(())
+  }
+
+  /// Multiplies an actor's scale by the given factors
+  pub fn scale_actor(&mut self, instance_name: &str, scale: &Vector<f32>) -> Result<()> {
+    let actor = self.find_actor_header_mut(instance_name)?;
+    actor.scale.x *= scale.x;
+    actor.scale.y *= scale.y;
+    actor.scale.z *= scale.z;
+    Ok(())
+  }
+
+  /// Replaces the object's property with the same name and index, or adds it
+  /// if the object doesn't have one yet
+  pub fn set_property(&mut self, instance_name: &str, property: Property) -> Result<()> {
+    let (_, object) = self.find_object_mut(instance_name)
+      .ok_or_else(|| ParseError::MissingObject(instance_name.to_string()))?;
+
+    let properties = object.get_properties_mut();
+    match properties.iter_mut().find(|p| p.name == property.name && p.index == property.index) {
+      Some(p) => *p = property,
+      None => properties.push(property),
+    }
+
+    Ok(())
+  }
+
+  /// Removes the object's properties with the given name. Returns whether
+  /// any were removed
+  pub fn remove_property(&mut self, instance_name: &str, property_name: &str) -> Result<bool> {
+    let (_, object) = self.find_object_mut(instance_name)
+      .ok_or_else(|| ParseError::MissingObject(instance_name.to_string()))?;
+
+    let properties = object.get_properties_mut();
+    let len = properties.len();
+    properties.retain(|p| p.name != property_name);
+
+    Ok(properties.len() != len)
+  }
+}
@@ -22,12 +22,6 @@ pub enum ParseError {
   #[error("Unknown object type: {0}")]
   UnknownObject(i32),
 
-  #[error("Unknown player type: {0}")]
-  UnknownPlayerType(u8),
-
-  #[error("Unknown player ID type: {0}")]
-  UnknownPlayerIDType(u8),
-
   #[error("Object longer than specified: {0}")]
   ObjectLength(String),
 
@@ -57,4 +51,28 @@ pub enum ParseError {
 
   #[error("Unknown Lua processor state storage struct type: {0}")]
   UnknownLuaProcessorStateStorageStructType(String),
+
+  #[error("Object not found: {0}")]
+  MissingObject(String),
+
+  #[error("Object is not an actor: {0}")]
+  NotAnActor(String),
+
+  #[error("Save has no levels")]
+  MissingLevel,
+
+  #[error("Invalid player ID: {0}")]
+  InvalidPlayerId(String),
+
+  #[error("Map marker ID doesn't fit in a byte: {0}")]
+  MarkerIdOutOfRange(i32),
+
+  #[error("Map marker ID already in use: {0}")]
+  DuplicateMarkerId(i32),
+
+  #[error("Lightweight buildables weren't decoded, so they can't be added to")]
+  UndecodedLightweightBuildables,
+
+  #[error("JSON error: {0}")]
+  Json(#[from] serde_json::Error),
 }
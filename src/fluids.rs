@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::graph::Graph;
+use crate::property::*;
+use crate::save::*;
+
+const PIPE_CONNECTION_PATHS: [&str; 2] = [
+  "/Script/FactoryGame.FGPipeConnectionComponent",
+  "/Script/FactoryGame.FGPipeConnectionFactory",
+];
+const PIPE_NETWORK_PATH: &str = "/Script/FactoryGame.FGPipeNetwork";
+
+/// Cubic metres per minute of pipelines by tier, Mk1 and Mk2
+pub const PIPE_FLOW_LIMITS: [f32; 2] = [300.0, 600.0];
+
+/// Metres a pump can lift fluid by tier, Mk1 and Mk2
+pub const PUMP_HEADLIFTS: [f32; 2] = [20.0, 50.0];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PipeNodeKind {
+  Pipe,
+  Pump,
+  Valve,
+  Junction,
+  Buffer,
+  /// Anything else with pipe connections, e.g. refineries, water
+  /// extractors or fluid freight platforms
+  Machine,
+}
+
+impl PipeNodeKind {
+  fn from_class_name(class_name: &str) -> PipeNodeKind {
+    if class_name.starts_with("Build_PipelinePump") {
+      PipeNodeKind::Pump
+    } else if class_name.starts_with("Build_PipelineJunction") {
+      PipeNodeKind::Junction
+    } else if class_name.starts_with("Build_Pipeline") {
+      PipeNodeKind::Pipe
+    } else if class_name == "Build_Valve_C" {
+      PipeNodeKind::Valve
+    } else if class_name == "Build_PipeStorageTank_C" || class_name == "Build_IndustrialTank_C" {
+      PipeNodeKind::Buffer
+    } else {
+      PipeNodeKind::Machine
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PipeNode {
+  pub instance_name: String,
+  pub class_name: String,
+  pub kind: PipeNodeKind,
+  /// Mark of a pipe or pump, 1 or 2
+  pub tier: Option<u8>,
+  /// Cubic metres per minute a pipe can carry
+  pub flow_limit: Option<f32>,
+  /// Metres a pump can lift fluid
+  pub headlift: Option<f32>,
+  /// Flow limit set by the player on a valve or pump
+  pub user_flow_limit: Option<f32>,
+  /// Cubic metres of fluid currently held by the building's fluid box
+  pub fluid_content: Option<f32>,
+  /// ID of the game's fluid integrity network the building belongs to
+  pub network_id: Option<i32>,
+}
+
+/// A link between two pipe connections. Fluid can flow either way so links
+/// are only stored once
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PipeEdge {
+  pub from_connection: String,
+  pub to_connection: String,
+}
+
+/// A group of pipes and buildings connected to each other
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FluidNetwork {
+  pub network_id: Option<i32>,
+  /// Path of the fluid's descriptor, e.g.
+  /// `/Game/FactoryGame/Resource/RawResources/Water/Desc_Water.Desc_Water_C`
+  pub fluid: Option<String>,
+  /// Indices of the network's nodes in the pipe graph
+  pub nodes: Vec<usize>,
+  /// Cubic metres held by every fluid box of the network
+  pub fluid_content: f32,
+  /// Flow limit of the slowest pipe in the network
+  pub flow_limit: Option<f32>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PipeGraph {
+  pub graph: Graph<PipeNode, PipeEdge>,
+  pub networks: Vec<FluidNetwork>,
+}
+
+/// Reads the mark from class names such as `Build_PipelineMK2_C` or
+/// `Build_PipelinePumpMk2_C`, where Mk1 has no suffix
+fn pipe_tier(class_name: &str) -> u8 {
+  if class_name.contains("MK2") || class_name.contains("Mk2") { 2 } else { 1 }
+}
+
+impl PipeGraph {
+  pub fn from_save(save: &Save) -> PipeGraph {
+    let mut graph: Graph<PipeNode, PipeEdge> = Graph::default();
+    let index = save.index();
+
+    let connections: Vec<(&ComponentHeader, &Object)> = save.iter_objects()
+      .filter_map(|(object_header, object)| match object_header {
+        ObjectHeader::Component(c) if PIPE_CONNECTION_PATHS.contains(&c.type_path.as_str()) => Some((c, object)),
+        _ => None,
+      })
+      .collect();
+    let parents: HashMap<&String, &String> = connections.iter()
+      .map(|(c, _)| (&c.instance_name, &c.parent_actor_name))
+      .collect();
+
+    let mut network_ids: HashMap<&String, i32> = HashMap::new();
+    for (c, object) in &connections {
+      if let Some(id) = property_int(object.get_properties(), "mPipeNetworkID") {
+        network_ids.entry(&c.parent_actor_name).or_insert(id);
+      }
+    }
+
+    for (c, _) in &connections {
+      if graph.index_of(&c.parent_actor_name).is_some() {
+        continue;
+      }
+      let Some((ObjectHeader::Actor(actor), object)) = index.find_object(&c.parent_actor_name) else {
+        continue;
+      };
+      let properties = object.get_properties();
+      let class = class_name(&actor.type_path);
+      let kind = PipeNodeKind::from_class_name(class);
+      let tier = matches!(kind, PipeNodeKind::Pipe | PipeNodeKind::Pump).then(|| pipe_tier(class));
+
+      graph.add_node(&actor.instance_name, PipeNode {
+        instance_name: actor.instance_name.clone(),
+        class_name: class.to_string(),
+        kind,
+        tier,
+        flow_limit: tier.filter(|_| kind == PipeNodeKind::Pipe).map(|t| PIPE_FLOW_LIMITS[t as usize - 1]),
+        headlift: tier.filter(|_| kind == PipeNodeKind::Pump).map(|t| PUMP_HEADLIFTS[t as usize - 1]),
+        user_flow_limit: property_f32(properties, "mUserFlowLimit").filter(|l| *l >= 0.0),
+        fluid_content: match find_property(properties, "mFluidBox").map(|p| &p.value) {
+          Some(PropertyValue::Struct((_, StructPropertyValue::FluidBox(f)))) => Some(*f),
+          _ => None,
+        },
+        network_id: network_ids.get(&actor.instance_name).copied(),
+      });
+    }
+
+    for (c, object) in &connections {
+      let Some(PropertyValue::Object(other)) = find_property(object.get_properties(), "mConnectedComponent").map(|p| &p.value) else {
+        continue;
+      };
+      if other.path_name.is_empty() || c.instance_name > other.path_name {
+        continue;
+      }
+      let Some(other_parent) = parents.get(&other.path_name) else {
+        continue;
+      };
+      if let (Some(a), Some(b)) = (graph.index_of(&c.parent_actor_name), graph.index_of(other_parent)) {
+        graph.add_edge(a, b, PipeEdge {
+          from_connection: c.instance_name.clone(),
+          to_connection: other.path_name.clone(),
+        });
+      }
+    }
+
+    let fluids: HashMap<i32, String> = save.iter_objects()
+      .filter(|(object_header, _)| object_header.get_type_path() == PIPE_NETWORK_PATH)
+      .filter_map(|(_, object)| {
+        let properties = object.get_properties();
+        let id = property_int(properties, "mPipeNetworkID")?;
+        Some((id, property_reference(properties, "mFluidDescriptor")?.clone()))
+      })
+      .collect();
+
+    let networks = graph.components().into_iter()
+      .map(|nodes| {
+        let network_id = nodes.iter().find_map(|i| graph.nodes[*i].network_id);
+        FluidNetwork {
+          network_id,
+          fluid: network_id.and_then(|id| fluids.get(&id).cloned()),
+          fluid_content: nodes.iter().filter_map(|i| graph.nodes[*i].fluid_content).sum(),
+          flow_limit: nodes.iter().filter_map(|i| graph.nodes[*i].flow_limit).reduce(f32::min),
+          nodes,
+        }
+      })
+      .collect();
+
+    PipeGraph { graph, networks }
+  }
+
+  pub fn node(&self, instance_name: &str) -> Option<&PipeNode> {
+    self.graph.node(instance_name)
+  }
+
+  /// Every building the given one is connected to through pipes
+  pub fn connected_to(&self, instance_name: &str) -> Vec<&PipeNode> {
+    match self.graph.index_of(instance_name) {
+      Some(start) => self.graph.connected(start).into_iter().map(|i| &self.graph.nodes[i]).collect(),
+      None => vec![],
+    }
+  }
+
+  /// Finds the network a building belongs to
+  pub fn network_of(&self, instance_name: &str) -> Option<&FluidNetwork> {
+    let i = self.graph.index_of(instance_name)?;
+    self.networks.iter().find(|n| n.nodes.contains(&i))
+  }
+}
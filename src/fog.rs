@@ -0,0 +1,152 @@
+use std::io::{self, Write};
+
+use flate2::write::ZlibEncoder;
+use flate2::{Compression, Crc};
+use serde::{Serialize, Deserialize};
+
+use crate::property::*;
+use crate::save::*;
+use crate::Result;
+
+const FOG_OF_WAR_PROPERTY: &str = "mFogOfWarRawData";
+
+/// Bounds of the area the fog of war texture covers, in world units
+const WORLD_MIN_X: f32 = -324698.83;
+const WORLD_MAX_X: f32 = 425301.83;
+const WORLD_MIN_Y: f32 = -375000.0;
+const WORLD_MAX_Y: f32 = 375000.0;
+
+/// Each texel is four bytes, of which the third holds how revealed it is
+const BYTES_PER_TEXEL: usize = 4;
+const REVEAL_CHANNEL: usize = 2;
+const REVEALED: u8 = 255;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// The map's fog of war as the raw RGBA texture the game saves, covering
+/// the world from north-west to south-east row by row
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FogOfWar {
+  pub width: usize,
+  pub height: usize,
+  pub data: Vec<u8>,
+}
+
+impl FogOfWar {
+  /// Wraps the saved bytes, which are always a square texture
+  pub fn from_bytes(data: Vec<u8>) -> FogOfWar {
+    let texels = data.len() / BYTES_PER_TEXEL;
+    let side = (texels as f64).sqrt() as usize;
+    let (width, height) = if side * side == texels { (side, side) } else { (texels, 1) };
+    FogOfWar { width, height, data }
+  }
+
+  /// How revealed the texel at a column and row is, from 0 to 255
+  pub fn revealed(&self, x: usize, y: usize) -> u8 {
+    self.data.get((y * self.width + x) * BYTES_PER_TEXEL + REVEAL_CHANNEL).copied().unwrap_or_default()
+  }
+
+  /// The fraction of the map which is fully revealed
+  pub fn revealed_fraction(&self) -> f32 {
+    let texels = self.width * self.height;
+    if texels == 0 {
+      return 0.0;
+    }
+    let revealed = self.data.chunks_exact(BYTES_PER_TEXEL).filter(|t| t[REVEAL_CHANNEL] == REVEALED).count();
+    revealed as f32 / texels as f32
+  }
+
+  pub fn reveal_all(&mut self) {
+    for texel in self.data.chunks_exact_mut(BYTES_PER_TEXEL) {
+      texel[REVEAL_CHANNEL] = REVEALED;
+    }
+  }
+
+  /// Reveals every texel whose center lies within `radius` of a point on
+  /// the X/Y plane, in world units
+  pub fn reveal_radius(&mut self, point: &Vector<f32>, radius: f32) {
+    let texel_width = (WORLD_MAX_X - WORLD_MIN_X) / self.width as f32;
+    let texel_height = (WORLD_MAX_Y - WORLD_MIN_Y) / self.height as f32;
+    for y in 0..self.height {
+      let dy = WORLD_MIN_Y + (y as f32 + 0.5) * texel_height - point.y;
+      for x in 0..self.width {
+        let dx = WORLD_MIN_X + (x as f32 + 0.5) * texel_width - point.x;
+        if dx * dx + dy * dy <= radius * radius {
+          if let Some(texel) = self.data.get_mut((y * self.width + x) * BYTES_PER_TEXEL + REVEAL_CHANNEL) {
+            *texel = REVEALED;
+          }
+        }
+      }
+    }
+  }
+
+  /// Writes the texture as an 8-bit RGBA PNG
+  pub fn write_png<W: Write>(&self, writer: &mut W) -> Result<()> {
+    let mut header: Vec<u8> = vec![];
+    header.extend_from_slice(&(self.width as u32).to_be_bytes());
+    header.extend_from_slice(&(self.height as u32).to_be_bytes());
+    // Bit depth, RGBA colour type, compression, filter and interlace methods
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    // Each row is prefixed by its filter type, here none
+    let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+    for row in self.data.chunks(self.width.max(1) * BYTES_PER_TEXEL) {
+      encoder.write_all(&[0])?;
+      encoder.write_all(row)?;
+    }
+    let image = encoder.finish()?;
+
+    writer.write_all(&PNG_SIGNATURE)?;
+    write_png_chunk(writer, b"IHDR", &header)?;
+    write_png_chunk(writer, b"IDAT", &image)?;
+    write_png_chunk(writer, b"IEND", &[])?;
+    Ok(())
+  }
+}
+
+/// Writes a chunk's length, type, data and the CRC of its type and data
+fn write_png_chunk<W: Write>(writer: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+  let mut crc = Crc::new();
+  crc.update(kind);
+  crc.update(data);
+
+  writer.write_all(&(data.len() as u32).to_be_bytes())?;
+  writer.write_all(kind)?;
+  writer.write_all(data)?;
+  writer.write_all(&crc.sum().to_be_bytes())
+}
+
+impl Save {
+  pub fn fog_of_war(&self) -> Option<FogOfWar> {
+    let manager = self.map_manager()?;
+    match find_property(manager.get_properties(), FOG_OF_WAR_PROPERTY).map(|p| &p.value) {
+      Some(PropertyValue::Array(a)) => Some(FogOfWar::from_bytes(a.elements.iter()
+        .flat_map(|e| match e {
+          ArrayPropertyValue::Bytes(b) => b.as_slice(),
+          ArrayPropertyValue::Byte(b) => std::slice::from_ref(b),
+          _ => &[],
+        })
+        .copied()
+        .collect())),
+      _ => None,
+    }
+  }
+
+  /// Replaces the saved fog of war with the given texture
+  pub fn set_fog_of_war(&mut self, fog: &FogOfWar) -> Result<()> {
+    let manager = self.map_manager_mut()?;
+
+    let elements = vec![ArrayPropertyValue::Bytes(fog.data.clone())];
+    let properties = manager.get_properties_mut();
+    match find_property_mut(properties, FOG_OF_WAR_PROPERTY).map(|p| &mut p.value) {
+      Some(PropertyValue::Array(a)) => a.elements = elements,
+      _ => properties.push(Property::new(FOG_OF_WAR_PROPERTY, PropertyValue::Array(ArrayProperty {
+        r#type: "Byte".to_string(),
+        struct_meta: None,
+        elements,
+      }))),
+    }
+
+    Ok(())
+  }
+}
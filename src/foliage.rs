@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::property::*;
+use crate::region::Region;
+use crate::save::*;
+
+const FOLIAGE_REMOVAL_PATH: &str = "/Script/FactoryGame.FGFoilageRemoval";
+const DESTROYED_FOLIAGE_PROPERTY: &str = "Destroyed_Foliage_Transform";
+
+/// Locations of foliage removed from one foliage type in one level, as
+/// recorded by a single property of a single object
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FoliageGroup {
+  pub level: String,
+  /// The foliage type, taken from the name of the object recording the
+  /// removals, e.g. `FoliageRemoval_Rock_01`
+  pub foliage_type: String,
+  pub instance_name: String,
+  pub property: String,
+  pub locations: Vec<Vector<f64>>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FoliageRemoval {
+  pub groups: Vec<FoliageGroup>,
+}
+
+fn narrow(v: &Vector<f64>) -> Vector<f32> {
+  Vector {
+    x: v.x as f32,
+    y: v.y as f32,
+    z: v.z as f32,
+  }
+}
+
+/// Strips the level prefix and any numeric suffix from an instance name
+fn foliage_type(instance_name: &str) -> &str {
+  let name = instance_name.rsplit('.').next().unwrap_or_default();
+  match name.rsplit_once('_') {
+    Some((prefix, suffix)) if suffix.parse::<u64>().is_ok() => prefix,
+    _ => name,
+  }
+}
+
+/// Reads the removed locations out of a property, if it records any
+fn removed_locations(object_header: &ObjectHeader, property: &Property) -> Option<Vec<Vector<f64>>> {
+  match &property.value {
+    PropertyValue::Set(s) if object_header.get_type_path() == FOLIAGE_REMOVAL_PATH => Some(s.values.iter()
+      .filter_map(|v| match v {
+        SetPropertyValue::Vector(v) => Some(Vector { x: v.x as f64, y: v.y as f64, z: v.z as f64 }),
+        _ => None,
+      })
+      .collect()),
+    PropertyValue::Map(m) if property.name == DESTROYED_FOLIAGE_PROPERTY => Some(m.keys.iter()
+      .filter_map(|k| match k {
+        MapPropertyKey::DoubleVector(v) => Some(v.clone()),
+        _ => None,
+      })
+      .collect()),
+    _ => None,
+  }
+}
+
+/// Removes the recorded locations for which `f` returns true from a
+/// property and returns how many were removed
+fn forget_locations<F: Fn(&Vector<f64>) -> bool>(object_header: &ObjectHeader, property: &mut Property, f: F) -> usize {
+  match &mut property.value {
+    PropertyValue::Set(s) if object_header.get_type_path() == FOLIAGE_REMOVAL_PATH => {
+      let len = s.values.len();
+      s.values.retain(|v| match v {
+        SetPropertyValue::Vector(v) => !f(&Vector { x: v.x as f64, y: v.y as f64, z: v.z as f64 }),
+        _ => true,
+      });
+      len - s.values.len()
+    },
+    PropertyValue::Map(m) if property.name == DESTROYED_FOLIAGE_PROPERTY => {
+      let len = m.keys.len();
+      let mut keys = vec![];
+      let mut values = vec![];
+      for (key, value) in m.keys.drain(..).zip(m.values.drain(..)) {
+        if !matches!(&key, MapPropertyKey::DoubleVector(v) if f(v)) {
+          keys.push(key);
+          values.push(value);
+        }
+      }
+      m.keys = keys;
+      m.values = values;
+      len - m.keys.len()
+    },
+    _ => 0,
+  }
+}
+
+/// Whether an object records removed foliage
+fn records_foliage(object_header: &ObjectHeader, object: &Object) -> bool {
+  object_header.get_type_path() == FOLIAGE_REMOVAL_PATH ||
+    find_property(object.get_properties(), DESTROYED_FOLIAGE_PROPERTY).is_some()
+}
+
+impl FoliageRemoval {
+  /// Total number of removed foliage instances
+  pub fn len(&self) -> usize {
+    self.groups.iter().map(|g| g.locations.len()).sum()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  pub fn count_by_type(&self) -> BTreeMap<&str, usize> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for group in &self.groups {
+      *counts.entry(group.foliage_type.as_str()).or_default() += group.locations.len();
+    }
+    counts
+  }
+
+  pub fn count_by_level(&self) -> BTreeMap<&str, usize> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for group in &self.groups {
+      *counts.entry(group.level.as_str()).or_default() += group.locations.len();
+    }
+    counts
+  }
+
+  /// Removed foliage within a region, with the group it belongs to
+  pub fn in_region<'a>(&'a self, region: &'a Region) -> impl Iterator<Item = (&'a FoliageGroup, &'a Vector<f64>)> {
+    self.groups.iter()
+      .flat_map(|g| g.locations.iter().map(move |l| (g, l)))
+      .filter(|(_, l)| region.contains(&narrow(l)))
+  }
+}
+
+impl Save {
+  /// Returns the foliage removed in every level, grouped by the object and
+  /// property recording it
+  pub fn foliage_removal(&self) -> FoliageRemoval {
+    let mut removal = FoliageRemoval::default();
+
+    for level in &self.levels {
+      for (object_header, object) in level.object_headers.iter().zip(&level.objects) {
+        if !records_foliage(object_header, object) {
+          continue;
+        }
+        for property in object.get_properties() {
+          let Some(locations) = removed_locations(object_header, property) else {
+            continue;
+          };
+          removal.groups.push(FoliageGroup {
+            level: level.name.clone(),
+            foliage_type: foliage_type(object_header.get_instance_name()).to_string(),
+            instance_name: object_header.get_instance_name().clone(),
+            property: property.name.clone(),
+            locations,
+          });
+        }
+      }
+    }
+
+    removal
+  }
+
+  /// Brings back the removed foliage within a region by forgetting its
+  /// removal. Returns how many instances were restored
+  pub fn restore_foliage(&mut self, region: &Region) -> usize {
+    let mut restored = 0;
+
+    for level in &mut self.levels {
+      for (object_header, object) in level.object_headers.iter().zip(level.objects.iter_mut()) {
+        if !records_foliage(object_header, object) {
+          continue;
+        }
+        for property in object.get_properties_mut() {
+          restored += forget_locations(object_header, property, |l| region.contains(&narrow(l)));
+        }
+      }
+    }
+
+    restored
+  }
+}
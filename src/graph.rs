@@ -0,0 +1,141 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Serialize, Deserialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Edge<E> {
+  pub from: usize,
+  pub to: usize,
+  pub data: E,
+}
+
+/// A directed graph of buildings keyed by instance name, shared by the
+/// logistics, pipe and transport networks. Nodes and edges are referred to
+/// by their index
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Graph<N, E> {
+  pub nodes: Vec<N>,
+  pub edges: Vec<Edge<E>>,
+  index: HashMap<String, usize>,
+  /// Indices into `edges` of the edges leaving and entering each node
+  edges_from: Vec<Vec<usize>>,
+  edges_to: Vec<Vec<usize>>,
+}
+
+impl<N, E> Default for Graph<N, E> {
+  fn default() -> Self {
+    Graph {
+      nodes: vec![],
+      edges: vec![],
+      index: HashMap::new(),
+      edges_from: vec![],
+      edges_to: vec![],
+    }
+  }
+}
+
+impl<N, E> Graph<N, E> {
+  /// Adds a node under the given instance name, returning its index. A node
+  /// which already exists under that name is kept as is
+  pub fn add_node(&mut self, instance_name: &str, node: N) -> usize {
+    if let Some(i) = self.index.get(instance_name) {
+      return *i;
+    }
+    self.nodes.push(node);
+    self.index.insert(instance_name.to_string(), self.nodes.len() - 1);
+    self.nodes.len() - 1
+  }
+
+  pub fn add_edge(&mut self, from: usize, to: usize, data: E) {
+    let len = self.edges_from.len().max(from + 1).max(to + 1);
+    self.edges_from.resize(len, vec![]);
+    self.edges_to.resize(len, vec![]);
+    self.edges_from[from].push(self.edges.len());
+    self.edges_to[to].push(self.edges.len());
+    self.edges.push(Edge { from, to, data });
+  }
+
+  pub fn index_of(&self, instance_name: &str) -> Option<usize> {
+    self.index.get(instance_name).copied()
+  }
+
+  pub fn node(&self, instance_name: &str) -> Option<&N> {
+    self.index_of(instance_name).map(|i| &self.nodes[i])
+  }
+
+  /// Indices into `edges` of the edges leaving a node
+  pub fn outgoing_edges(&self, node: usize) -> &[usize] {
+    self.edges_from.get(node).map_or(&[], |e| e.as_slice())
+  }
+
+  /// Indices into `edges` of the edges entering a node
+  pub fn incoming_edges(&self, node: usize) -> &[usize] {
+    self.edges_to.get(node).map_or(&[], |e| e.as_slice())
+  }
+
+  pub fn outgoing(&self, node: usize) -> impl Iterator<Item = &Edge<E>> {
+    self.outgoing_edges(node).iter().map(|k| &self.edges[*k])
+  }
+
+  pub fn incoming(&self, node: usize) -> impl Iterator<Item = &Edge<E>> {
+    self.incoming_edges(node).iter().map(|k| &self.edges[*k])
+  }
+
+  /// Indices of the nodes reachable from `start` by following edges
+  /// forwards (or backwards), in breadth-first order and excluding `start`
+  pub fn reachable(&self, start: usize, forwards: bool) -> Vec<usize> {
+    if forwards {
+      bfs(start, |i| self.outgoing(i).map(|e| e.to))
+    } else {
+      bfs(start, |i| self.incoming(i).map(|e| e.from))
+    }
+  }
+
+  /// Indices of the nodes reachable from `start` ignoring edge direction,
+  /// excluding `start`
+  pub fn connected(&self, start: usize) -> Vec<usize> {
+    bfs(start, |i| self.neighbours(i))
+  }
+
+  /// Splits the graph into groups of nodes connected to each other,
+  /// ignoring edge direction
+  pub fn components(&self) -> Vec<Vec<usize>> {
+    let mut seen = vec![false; self.nodes.len()];
+    let mut components: Vec<Vec<usize>> = vec![];
+    for start in 0..self.nodes.len() {
+      if seen[start] {
+        continue;
+      }
+      let mut component = vec![start];
+      component.extend(bfs(start, |i| self.neighbours(i)));
+      for i in &component {
+        seen[*i] = true;
+      }
+      component.sort();
+      components.push(component);
+    }
+
+    components
+  }
+
+  fn neighbours(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+    self.outgoing(node).map(|e| e.to).chain(self.incoming(node).map(|e| e.from))
+  }
+}
+
+fn bfs<I: Iterator<Item = usize>>(start: usize, neighbours: impl Fn(usize) -> I) -> Vec<usize> {
+  let mut visited: Vec<usize> = vec![];
+  let mut seen = HashSet::from([start]);
+  let mut queue = VecDeque::from([start]);
+
+  while let Some(node) = queue.pop_front() {
+    for next in neighbours(node) {
+      if seen.insert(next) {
+        visited.push(next);
+        queue.push_back(next);
+      }
+    }
+  }
+
+  visited
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::graph::Graph;
+use crate::property::*;
+use crate::save::*;
+use crate::spline::Spline;
+
+const HYPER_CONNECTION_PATH: &str = "/Script/FactoryGame.FGPipeConnectionComponentHyper";
+const ELEVATOR_CLASS: &str = "Build_Elevator_C";
+const ELEVATOR_FLOOR_STOP_CLASS: &str = "Build_ElevatorFloorStop_C";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HypertubeNodeKind {
+  Segment,
+  Entrance,
+  Junction,
+  /// Supports, wall supports and wall holes, which tubes pass through
+  Support,
+}
+
+impl HypertubeNodeKind {
+  pub fn from_class_name(class_name: &str) -> Option<HypertubeNodeKind> {
+    if class_name.contains("Junction") {
+      Some(HypertubeNodeKind::Junction)
+    } else if class_name.starts_with("Build_PipeHyperStart") {
+      Some(HypertubeNodeKind::Entrance)
+    } else if class_name.starts_with("Build_PipeHyperSupport") || class_name.starts_with("Build_HyperTubeWall") || class_name.starts_with("Build_HyperPole") {
+      Some(HypertubeNodeKind::Support)
+    } else if class_name.starts_with("Build_PipeHyper") {
+      Some(HypertubeNodeKind::Segment)
+    } else {
+      None
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HypertubeNode {
+  pub instance_name: String,
+  pub class_name: String,
+  pub kind: HypertubeNodeKind,
+  pub position: Vector<f32>,
+  /// A segment's curve in world space
+  pub spline: Option<Spline>,
+  /// Connections which aren't linked to anything
+  pub open_connections: Vec<String>,
+}
+
+/// A link between two hypertube connections. Players can travel either way
+/// so links are only stored once
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HypertubeEdge {
+  pub from_connection: String,
+  pub to_connection: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HypertubeNetwork {
+  pub graph: Graph<HypertubeNode, HypertubeEdge>,
+}
+
+/// A stop of a personnel elevator
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ElevatorStop {
+  pub instance_name: String,
+  pub name: Option<String>,
+  pub position: Vector<f32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Elevator {
+  pub instance_name: String,
+  pub position: Vector<f32>,
+  /// Floor stops from the bottom up
+  pub stops: Vec<ElevatorStop>,
+}
+
+impl HypertubeNetwork {
+  pub fn from_save(save: &Save) -> HypertubeNetwork {
+    let mut graph: Graph<HypertubeNode, HypertubeEdge> = Graph::default();
+    let index = save.index();
+
+    let connections: Vec<(&ComponentHeader, &Object)> = save.iter_objects()
+      .filter_map(|(object_header, object)| match object_header {
+        ObjectHeader::Component(c) if c.type_path == HYPER_CONNECTION_PATH => Some((c, object)),
+        _ => None,
+      })
+      .collect();
+    let parents: HashMap<&String, &String> = connections.iter()
+      .map(|(c, _)| (&c.instance_name, &c.parent_actor_name))
+      .collect();
+
+    for (c, _) in &connections {
+      if graph.index_of(&c.parent_actor_name).is_some() {
+        continue;
+      }
+      let Some((ObjectHeader::Actor(actor), object)) = index.find_object(&c.parent_actor_name) else {
+        continue;
+      };
+      let class = class_name(&actor.type_path);
+      let Some(kind) = HypertubeNodeKind::from_class_name(class) else {
+        continue;
+      };
+
+      graph.add_node(&actor.instance_name, HypertubeNode {
+        instance_name: actor.instance_name.clone(),
+        class_name: class.to_string(),
+        kind,
+        position: actor.position.clone(),
+        spline: Spline::from_properties(object.get_properties()).map(|s| s.to_world(actor)),
+        open_connections: vec![],
+      });
+    }
+
+    for (c, object) in &connections {
+      let Some(i) = graph.index_of(&c.parent_actor_name) else {
+        continue;
+      };
+      let other = property_reference(object.get_properties(), "mConnectedComponent")
+        .and_then(|other| Some((other, graph.index_of(parents.get(other)?)?)));
+      let Some((other, j)) = other else {
+        graph.nodes[i].open_connections.push(c.instance_name.clone());
+        continue;
+      };
+      if c.instance_name < *other {
+        graph.add_edge(i, j, HypertubeEdge {
+          from_connection: c.instance_name.clone(),
+          to_connection: other.clone(),
+        });
+      }
+    }
+
+    HypertubeNetwork { graph }
+  }
+
+  pub fn node(&self, instance_name: &str) -> Option<&HypertubeNode> {
+    self.graph.node(instance_name)
+  }
+
+  pub fn entrances(&self) -> impl Iterator<Item = &HypertubeNode> {
+    self.graph.nodes.iter().filter(|n| n.kind == HypertubeNodeKind::Entrance)
+  }
+
+  /// The other entrances a player can travel to from the given one
+  pub fn destinations(&self, entrance: &str) -> Vec<&HypertubeNode> {
+    match self.graph.index_of(entrance) {
+      Some(start) => self.graph.connected(start).into_iter()
+        .map(|i| &self.graph.nodes[i])
+        .filter(|n| n.kind == HypertubeNodeKind::Entrance)
+        .collect(),
+      None => vec![],
+    }
+  }
+
+  /// Segments and entrances with an end which isn't connected to anything,
+  /// where players get thrown out of the network. Junctions and supports
+  /// are left out since they may have unused sides
+  pub fn open_ends(&self) -> impl Iterator<Item = &HypertubeNode> {
+    self.graph.nodes.iter().filter(|n| {
+      matches!(n.kind, HypertubeNodeKind::Segment | HypertubeNodeKind::Entrance) && !n.open_connections.is_empty()
+    })
+  }
+
+  /// Groups of connected tubes without an entrance, which no player can
+  /// get into
+  pub fn unreachable(&self) -> Vec<Vec<&HypertubeNode>> {
+    self.graph.components().into_iter()
+      .map(|nodes| nodes.into_iter().map(|i| &self.graph.nodes[i]).collect::<Vec<_>>())
+      .filter(|nodes| nodes.iter().all(|n| n.kind != HypertubeNodeKind::Entrance))
+      .collect()
+  }
+}
+
+impl Save {
+  /// Returns every personnel elevator with its floor stops
+  pub fn elevators(&self) -> Vec<Elevator> {
+    let mut elevators: Vec<Elevator> = vec![];
+    let mut stops: Vec<(Option<&String>, ElevatorStop)> = vec![];
+
+    for (object_header, object) in self.iter_objects() {
+      let ObjectHeader::Actor(actor) = object_header else {
+        continue;
+      };
+      let properties = object.get_properties();
+      match class_name(&actor.type_path) {
+        ELEVATOR_CLASS => elevators.push(Elevator {
+          instance_name: actor.instance_name.clone(),
+          position: actor.position.clone(),
+          stops: vec![],
+        }),
+        ELEVATOR_FLOOR_STOP_CLASS => stops.push((property_reference(properties, "mElevator"), ElevatorStop {
+          instance_name: actor.instance_name.clone(),
+          name: properties.iter().find_map(|p| match &p.value {
+            PropertyValue::String(s) if p.name.contains("Name") => Some(s.clone()),
+            _ => None,
+          }),
+          position: actor.position.clone(),
+        })),
+        _ => {},
+      }
+    }
+
+    for (elevator_name, stop) in stops {
+      // Stops without a recorded elevator belong to the one directly above
+      // or below them
+      let elevator = match elevator_name {
+        Some(name) => elevators.iter_mut().find(|e| &e.instance_name == name),
+        None => elevators.iter_mut().find(|e| {
+          (e.position.x - stop.position.x).abs() < 1.0 && (e.position.y - stop.position.y).abs() < 1.0
+        }),
+      };
+      if let Some(elevator) = elevator {
+        elevator.stops.push(stop);
+      }
+    }
+    for elevator in &mut elevators {
+      elevator.stops.sort_by(|a, b| a.position.z.total_cmp(&b.position.z));
+    }
+
+    elevators
+  }
+}
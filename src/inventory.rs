@@ -0,0 +1,319 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Serialize, Deserialize};
+
+use crate::property::*;
+use crate::save::*;
+
+const INVENTORY_COMPONENT_PATH: &str = "/Script/FactoryGame.FGInventoryComponent";
+const CENTRAL_STORAGE_SUBSYSTEM_PATH: &str = "/Game/FactoryGame/-Shared/Blueprint/BP_CentralStorageSubsystem.BP_CentralStorageSubsystem_C";
+const PLAYER_PATH: &str = "/Game/FactoryGame/Character/Player/Char_Player.Char_Player_C";
+const VEHICLE_PATH: &str = "/Buildable/Vehicle/";
+const CRATE_CLASS: &str = "BP_Crate_C";
+
+/// An amount of a single item held in one inventory slot
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemStack {
+  /// Path of the item's descriptor, e.g.
+  /// `/Game/FactoryGame/Resource/Parts/IronPlate/Desc_IronPlate.Desc_IronPlate_C`
+  pub item: String,
+  pub count: i32,
+}
+
+impl ItemStack {
+  /// Reads a stack from the properties of an `InventoryStack` struct. Returns
+  /// None for empty slots
+  pub fn from_properties(properties: &[Property]) -> Option<ItemStack> {
+    let item = match find_property(properties, "Item").map(|p| &p.value) {
+      Some(PropertyValue::Struct((_, StructPropertyValue::InventoryItem(item)))) => &item.item_name,
+      _ => return None,
+    };
+    let count = match find_property(properties, "NumItems").map(|p| &p.value) {
+      Some(PropertyValue::Int(n)) => *n,
+      _ => 0,
+    };
+
+    if item.is_empty() {
+      return None;
+    }
+
+    Some(ItemStack {
+      item: item.clone(),
+      count,
+    })
+  }
+
+  pub fn class_name(&self) -> &str {
+    class_name(&self.item)
+  }
+}
+
+/// What an inventory belongs to, for grouping totals
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum InventoryOwnerKind {
+  Storage,
+  Player,
+  Vehicle,
+  Machine,
+  DimensionalDepot,
+  Other,
+}
+
+impl InventoryOwnerKind {
+  fn from_type_path(type_path: &str) -> InventoryOwnerKind {
+    let class = class_name(type_path);
+    if type_path == PLAYER_PATH {
+      InventoryOwnerKind::Player
+    } else if type_path.contains(VEHICLE_PATH) {
+      InventoryOwnerKind::Vehicle
+    } else if (class.contains("Storage") && !class.contains("PowerStorage")) || class == CRATE_CLASS {
+      InventoryOwnerKind::Storage
+    } else if type_path.contains("/Buildable/") {
+      InventoryOwnerKind::Machine
+    } else {
+      InventoryOwnerKind::Other
+    }
+  }
+}
+
+/// An inventory component along with the actor it belongs to
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Inventory {
+  pub instance_name: String,
+  pub owner: String,
+  pub owner_kind: InventoryOwnerKind,
+  /// Every slot in order, None for empty ones
+  pub slots: Vec<Option<ItemStack>>,
+  /// Descriptor paths of the only items allowed in each slot, if restricted
+  pub allowed_items: Vec<String>,
+  /// Stack sizes of slots which don't use the item's own stack size
+  pub arbitrary_slot_sizes: Vec<i32>,
+}
+
+impl Inventory {
+  fn from_object(component: &ComponentHeader, object: &Object, owner_kind: InventoryOwnerKind) -> Inventory {
+    let properties = object.get_properties();
+
+    let slots = match find_property(properties, "mInventoryStacks").map(|p| &p.value) {
+      Some(PropertyValue::Array(a)) => a.elements.iter()
+        .map(|e| match e {
+          ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(properties)) => ItemStack::from_properties(properties),
+          _ => None,
+        })
+        .collect(),
+      _ => vec![],
+    };
+
+    let allowed_items = match find_property(properties, "mAllowedItemDescriptors").map(|p| &p.value) {
+      Some(PropertyValue::Array(a)) => a.elements.iter()
+        .map(|e| match e {
+          ArrayPropertyValue::Object(o) => o.path_name.clone(),
+          _ => String::new(),
+        })
+        .collect(),
+      _ => vec![],
+    };
+
+    let arbitrary_slot_sizes = match find_property(properties, "mArbitrarySlotSizes").map(|p| &p.value) {
+      Some(PropertyValue::Array(a)) => a.elements.iter()
+        .filter_map(|e| match e {
+          ArrayPropertyValue::Int(i) => Some(*i),
+          _ => None,
+        })
+        .collect(),
+      _ => vec![],
+    };
+
+    Inventory {
+      instance_name: component.instance_name.clone(),
+      owner: component.parent_actor_name.clone(),
+      owner_kind,
+      slots,
+      allowed_items,
+      arbitrary_slot_sizes,
+    }
+  }
+
+  /// The non-empty slots
+  pub fn stacks(&self) -> impl Iterator<Item = &ItemStack> {
+    self.slots.iter().flatten()
+  }
+
+  /// Number of each item held, keyed by descriptor path
+  pub fn totals(&self) -> BTreeMap<String, i64> {
+    let mut totals: BTreeMap<String, i64> = BTreeMap::new();
+    for stack in self.stacks() {
+      *totals.entry(stack.item.clone()).or_default() += stack.count as i64;
+    }
+    totals
+  }
+
+  /// Number of an item held, by class name or descriptor path
+  pub fn count_of(&self, item: &str) -> i64 {
+    self.stacks()
+      .filter(|s| s.item == item || s.class_name() == item)
+      .map(|s| s.count as i64)
+      .sum()
+  }
+}
+
+/// Where a single item is held across a save
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ItemLocations {
+  pub total: i64,
+  pub by_kind: BTreeMap<InventoryOwnerKind, i64>,
+  /// Instance names of the owning actors with the number they hold
+  pub by_owner: Vec<(String, i64)>,
+}
+
+impl Save {
+  /// Returns every inventory component in the save
+  pub fn inventories(&self) -> Vec<Inventory> {
+    let owner_kinds: HashMap<&String, InventoryOwnerKind> = self.iter_objects()
+      .filter_map(|(object_header, _)| match object_header {
+        ObjectHeader::Actor(a) => Some((&a.instance_name, InventoryOwnerKind::from_type_path(&a.type_path))),
+        ObjectHeader::Component(_) => None,
+      })
+      .collect();
+
+    self.iter_objects()
+      .filter_map(|(object_header, object)| match object_header {
+        ObjectHeader::Component(c) if c.type_path == INVENTORY_COMPONENT_PATH => {
+          let owner_kind = owner_kinds.get(&c.parent_actor_name).copied().unwrap_or(InventoryOwnerKind::Other);
+          Some(Inventory::from_object(c, object, owner_kind))
+        },
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// Returns a single inventory component by instance name
+  pub fn inventory(&self, instance_name: &str) -> Option<Inventory> {
+    self.index().inventory(instance_name)
+  }
+
+  /// Returns the non-empty stacks of an inventory component
+  pub fn inventory_stacks(&self, instance_name: &str) -> Vec<ItemStack> {
+    self.index().inventory_stacks(instance_name)
+  }
+
+  /// Items stored in the dimensional depot, keyed by descriptor path
+  pub fn dimensional_depot(&self) -> BTreeMap<String, i64> {
+    let mut totals: BTreeMap<String, i64> = BTreeMap::new();
+
+    let subsystem = self.iter_objects().find(|(object_header, _)| object_header.get_type_path() == CENTRAL_STORAGE_SUBSYSTEM_PATH);
+    let Some((_, object)) = subsystem else {
+      return totals;
+    };
+
+    if let Some(PropertyValue::Array(a)) = find_property(object.get_properties(), "mStoredItems").map(|p| &p.value) {
+      for element in &a.elements {
+        let ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(properties)) = element else {
+          continue;
+        };
+        let Some(item) = property_reference(properties, "ItemClass") else {
+          continue;
+        };
+        if let Some(amount) = property_int(properties, "Amount") {
+          *totals.entry(item.clone()).or_default() += amount as i64;
+        }
+      }
+    }
+
+    totals
+  }
+
+  /// Number of each item held across every inventory and the dimensional
+  /// depot, keyed by descriptor path
+  pub fn item_totals(&self) -> BTreeMap<String, i64> {
+    let mut totals = self.dimensional_depot();
+    for inventory in self.inventories() {
+      for (item, count) in inventory.totals() {
+        *totals.entry(item).or_default() += count;
+      }
+    }
+    totals
+  }
+
+  /// Number of each item held by each kind of owner, e.g. everything held
+  /// by players or in vehicles
+  pub fn item_totals_by_kind(&self) -> BTreeMap<InventoryOwnerKind, BTreeMap<String, i64>> {
+    let mut totals: BTreeMap<InventoryOwnerKind, BTreeMap<String, i64>> = BTreeMap::new();
+    for inventory in self.inventories() {
+      let kind_totals = totals.entry(inventory.owner_kind).or_default();
+      for (item, count) in inventory.totals() {
+        *kind_totals.entry(item).or_default() += count;
+      }
+    }
+    let depot = self.dimensional_depot();
+    if !depot.is_empty() {
+      totals.insert(InventoryOwnerKind::DimensionalDepot, depot);
+    }
+    totals
+  }
+
+  /// Number of each item held by each actor, keyed by the actor's instance
+  /// name, summing all of an actor's inventories
+  pub fn item_totals_by_owner(&self) -> BTreeMap<String, BTreeMap<String, i64>> {
+    let mut totals: BTreeMap<String, BTreeMap<String, i64>> = BTreeMap::new();
+    for inventory in self.inventories() {
+      let owner_totals = totals.entry(inventory.owner.clone()).or_default();
+      for (item, count) in inventory.totals() {
+        *owner_totals.entry(item).or_default() += count;
+      }
+    }
+    totals.retain(|_, t| !t.is_empty());
+    totals
+  }
+
+  /// Finds everywhere an item is held, by class name (`Desc_ModularFrameHeavy_C`)
+  /// or descriptor path
+  pub fn locate_item(&self, item: &str) -> ItemLocations {
+    let mut locations = ItemLocations::default();
+    let mut by_owner: BTreeMap<String, i64> = BTreeMap::new();
+
+    for inventory in self.inventories() {
+      let count = inventory.count_of(item);
+      if count > 0 {
+        locations.total += count;
+        *locations.by_kind.entry(inventory.owner_kind).or_default() += count;
+        *by_owner.entry(inventory.owner).or_default() += count;
+      }
+    }
+
+    let depot: i64 = self.dimensional_depot().iter()
+      .filter(|(path, _)| *path == item || class_name(path) == item)
+      .map(|(_, count)| count)
+      .sum();
+    if depot > 0 {
+      locations.total += depot;
+      locations.by_kind.insert(InventoryOwnerKind::DimensionalDepot, depot);
+    }
+
+    locations.by_owner = by_owner.into_iter().collect();
+    locations
+  }
+}
+
+impl SaveIndex<'_> {
+  /// Same as `Save::inventory`
+  pub fn inventory(&self, instance_name: &str) -> Option<Inventory> {
+    match self.find_object(instance_name)? {
+      (ObjectHeader::Component(c), object) => {
+        let owner_kind = match self.find_object(&c.parent_actor_name) {
+          Some((object_header, _)) => InventoryOwnerKind::from_type_path(object_header.get_type_path()),
+          None => InventoryOwnerKind::Other,
+        };
+        Some(Inventory::from_object(c, object, owner_kind))
+      },
+      _ => None,
+    }
+  }
+
+  /// Same as `Save::inventory_stacks`
+  pub fn inventory_stacks(&self, instance_name: &str) -> Vec<ItemStack> {
+    self.inventory(instance_name)
+      .map(|i| i.slots.into_iter().flatten().collect())
+      .unwrap_or_default()
+  }
+}
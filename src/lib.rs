@@ -10,9 +10,35 @@ use byteorder::{ByteOrder, LittleEndian, ReadBytesExt};
 use flate2::bufread::ZlibDecoder;
 use log::{debug, warn};
 
+pub mod buildings;
+pub mod collectibles;
+pub mod customization;
+pub mod diff;
+pub mod edit;
 pub mod errors;
+pub mod fog;
+pub mod foliage;
+pub mod fluids;
+pub mod graph;
+pub mod hypertubes;
+pub mod inventory;
+pub mod lightweight;
+pub mod logistics;
+pub mod markers;
+pub mod players;
+pub mod power;
+pub mod production;
+pub mod progression;
 pub mod property;
+pub mod rails;
+pub mod region;
+pub mod resources;
 pub mod save;
+pub mod spline;
+pub mod timeline;
+pub mod vehicles;
+pub mod visit;
+pub mod write;
 
 use crate::errors::ParseError;
 use crate::property::*;
@@ -58,7 +84,7 @@ pub fn read_file<P: AsRef<Path>>(path: P) -> result::Result<Save, ParseError> {
   // Establishes a new cursor for the decompressed body bytes
   let mut body_cursor = io::Cursor::new(body_bytes);
 
-  // TODO: What is this?
+  // The size of the rest of the body
   body_cursor.seek_relative(8)?;
 
   // Reads the partitions and levels and returns the complete save file object
@@ -66,13 +92,35 @@ pub fn read_file<P: AsRef<Path>>(path: P) -> result::Result<Save, ParseError> {
   let partitions = body_cursor.read_partitions::<LittleEndian>()?;
   let levels = body_cursor.read_levels::<LittleEndian>(&header)?;
 
+  // Nothing is known to follow the levels, but anything which does is kept
+  let mut trailing: Vec<u8> = vec![];
+  body_cursor.read_to_end(&mut trailing)?;
+  if !trailing.is_empty() {
+    warn!("{} bytes after the last level", trailing.len());
+  }
+
   Ok(Save {
     header,
     partitions,
     levels,
+    trailing,
   })
 }
 
+/// Reads only the header of the file at the given path, which is much faster
+/// than reading the whole file when only its metadata is needed (e.g. to find
+/// every save belonging to a session)
+pub fn read_file_header<P: AsRef<Path>>(path: P) -> result::Result<Header, ParseError> {
+  let mut reader = io::BufReader::new(fs::File::open(&path)?);
+
+  let header = reader.read_header::<LittleEndian>()?;
+  if header.save_file_version < MIN_SAVE_FILE_VERSION {
+    return Err(ParseError::UnsupportedFileVersion(header.save_file_version, MIN_SAVE_FILE_VERSION))
+  }
+
+  Ok(header)
+}
+
 /// Extends `byteorder`'s `ReadBytesExt` (which itself extends `io::Read`)
 /// and `io::Seek` to build a robust byte reader with many great
 /// utility functions needed to support the custom save file format
@@ -294,26 +342,22 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
     let mut partitions = Partitions::default();
     let num_partitions = self.read_i32::<E>()?;
 
-    self.seek_length_prefixed_string::<E>()?;
-    self.seek_relative(12)?;
-    self.seek_length_prefixed_string::<E>()?;
-    self.seek_relative(4)?;
-
-    for _ in 1..num_partitions {
-      let key = self.read_length_prefixed_string::<E>()?;
-
-      let mut partition = Partition::default();
-
-      self.seek_relative(8)?;
+    for _ in 0..num_partitions {
+      let mut partition = Partition {
+        name: self.read_length_prefixed_string::<E>()?,
+        cell_size: self.read_i32::<E>()?,
+        hash: self.read_u32::<E>()?,
+        levels: vec![],
+      };
 
       let num_levels = self.read_i32::<E>()?;
       for _ in 0..num_levels {
         let level_key = self.read_length_prefixed_string::<E>()?;
         let level_value = self.read_u32::<E>()?;
-        partition.levels.insert(level_key, level_value);
+        partition.levels.push((level_key, level_value));
       }
 
-      partitions.partitions.insert(key, Partition::default());
+      partitions.partitions.push(partition);
     }
     Ok(partitions)
   }
@@ -328,12 +372,154 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
 
     if &level_name != map_name {
       object.set_level_name(level_name);
+    } else {
+      object.set_level_is_map();
     }
     object.set_path_name(path_name);
 
     Ok(())
   }
 
+  /// Reads a level name and a path name as they are, without leaving out a
+  /// level name which matches the map name
+  fn read_object_reference_raw<E: ByteOrder>(&mut self) -> Result<ObjectReference> {
+    Ok(ObjectReference {
+      level_name: self.read_length_prefixed_string::<E>()?,
+      path_name: self.read_length_prefixed_string::<E>()?,
+      level_is_map: false,
+    })
+  }
+
+  /// Reads the instances of each class held by the lightweight buildable
+  /// subsystem, which 1.0 saves use for foundations, walls, beams etc.
+  fn read_lightweight_buildables<E: ByteOrder>(&mut self) -> Result<LightweightBuildables> {
+    let mut extra = LightweightBuildables {
+      count: self.read_i32::<E>()?,
+      ..Default::default()
+    };
+
+    let num_classes = self.read_i32::<E>()?;
+    for _ in 0..num_classes {
+      let mut class = LightweightBuildableClass {
+        class: self.read_object_reference_raw::<E>()?,
+        instances: vec![],
+      };
+
+      let num_instances = self.read_i32::<E>()?;
+      for _ in 0..num_instances {
+        let transform = Transform {
+          rotation: self.read_quaternion_double::<E>()?,
+          translation: self.read_vector_double::<E>()?,
+          scale: self.read_vector_double::<E>()?,
+        };
+        let customization = Customization {
+          swatch: self.read_object_reference_raw::<E>()?,
+          material: self.read_object_reference_raw::<E>()?,
+          pattern: self.read_object_reference_raw::<E>()?,
+          skin: self.read_object_reference_raw::<E>()?,
+          primary_color: self.read_color::<E>()?,
+          secondary_color: self.read_color::<E>()?,
+          paint_finish: self.read_object_reference_raw::<E>()?,
+          pattern_rotation: self.read_u8()?,
+        };
+        class.instances.push(LightweightInstance {
+          transform,
+          customization,
+          built_with_recipe: self.read_object_reference_raw::<E>()?,
+          blueprint_proxy: self.read_object_reference_raw::<E>()?,
+        });
+      }
+
+      extra.classes.push(class);
+    }
+
+    Ok(extra)
+  }
+
+  /// Reads the physics state of each of a vehicle's bodies
+  fn read_vehicle_extra<E: ByteOrder>(&mut self) -> Result<VehicleExtra> {
+    let mut extra = VehicleExtra {
+      count: self.read_i32::<E>()?,
+      ..Default::default()
+    };
+
+    let num_elements = self.read_i32::<E>()?;
+    for _ in 0..num_elements {
+      extra.elements.push(Vehicle {
+        name: self.read_length_prefixed_string::<E>()?,
+        state: RigidBodyState {
+          position: self.read_vector_double::<E>()?,
+          rotation: self.read_quaternion_double::<E>()?,
+          linear_velocity: self.read_vector_double::<E>()?,
+          angular_velocity: self.read_vector_double::<E>()?,
+          flags: self.read_u8()?,
+        },
+      });
+    }
+
+    Ok(extra)
+  }
+
+  /// Reads a player state's ID, which ends at `end`
+  /// Reads a player state's count and ID up to `end`. A type byte after the
+  /// count gives the ID's layout:
+  /// - 3: no ID follows
+  /// - 8: a platform ID string
+  /// - 17: an Epic ID as a `u8` length and bytes
+  /// - 25, 29: a Steam ID as a `u8` length and bytes
+  /// - 241: an ID type byte (1 for Epic, 6 for Steam) then an `i32` length
+  ///   and bytes
+  /// - 248: an unknown string, then the Epic ID as a string
+  /// - 249: an unknown string and no ID
+  fn read_player_state<E: ByteOrder>(&mut self, end: u64) -> Result<PlayerState> {
+    let mut player_state = PlayerState {
+      count: self.read_i32::<E>()?,
+      ..Default::default()
+    };
+    let player_type = self.read_u8()?;
+    player_state.id = match player_type {
+      // Nothing follows, presumably for a player who never had an ID
+      3 => None,
+      8 => Some(PlayerId::Platform(self.read_length_prefixed_string::<E>()?)),
+      17 => Some(PlayerId::from_epic_bytes(player_type, &self.read_platform_id::<E>()?)),
+      25 | 29 => Some(PlayerId::from_steam_bytes(player_type, &self.read_platform_id::<E>()?)),
+      241 => {
+        let id_type = self.read_u8()?;
+        // The length comes from the file, so it's kept within the object
+        let remaining = end.saturating_sub(self.stream_position()?);
+        let len = (self.read_i32::<E>()?.max(0) as u64).min(remaining.saturating_sub(4));
+        let mut bytes = vec![0; len as usize];
+        self.read_exact(&mut bytes)?;
+
+        match id_type {
+          1 => Some(PlayerId::from_epic_bytes(id_type, &bytes)),
+          6 => Some(PlayerId::from_steam_bytes(id_type, &bytes)),
+          _ => Some(PlayerId::Unknown { kind: id_type, bytes }),
+        }
+      },
+      248 => {
+        // What the first string holds is unknown
+        self.seek_length_prefixed_string::<E>()?;
+        Some(PlayerId::from_epic_string(&self.read_length_prefixed_string::<E>()?))
+      },
+      249 => {
+        // What the string holds is unknown, it's kept only in `raw`
+        self.seek_length_prefixed_string::<E>()?;
+        None
+      },
+      _ => {
+        // The layout is unknown, so everything left in the object is
+        // taken as the ID
+        let remaining = end.saturating_sub(self.stream_position()?);
+        let mut bytes = vec![0; remaining as usize];
+        self.read_exact(&mut bytes)?;
+        Some(PlayerId::Unknown { kind: player_type, bytes })
+      },
+    };
+
+    Ok(player_state)
+  }
+
   /// Reads an object of type `Component`'s header
   fn read_component_header<E: ByteOrder>(&mut self, map_name: &String) -> Result<ComponentHeader> {
     let mut component_header = ComponentHeader::default();
@@ -371,16 +557,13 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
     }
   }
 
-  /// Reads and parses an ID for the launching platform (Steam, Epic Online Services, etc)
-  fn read_platform_id<E: ByteOrder>(&mut self) -> Result<String> {
+  /// Reads the length-prefixed bytes of an ID for the launching platform
+  /// (Steam, Epic Online Services, etc)
+  fn read_platform_id<E: ByteOrder>(&mut self) -> Result<Vec<u8>> {
     let len = self.read_u8()?;
-    let mut id = String::new();
-    for _ in 0..len {
-      let byte = self.read_u8()?;
-      let hex_byte = format!("{:02x}", byte);
-      id.push_str(&hex_byte)
-    }
-    Ok(id.trim_start_matches("0").to_string())
+    let mut bytes = vec![0; len as usize];
+    self.read_exact(&mut bytes)?;
+    Ok(bytes)
   }
 
   /// Reads a byte flag to determine if the following 16 bytes will be a
@@ -467,13 +650,19 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
           )
         },
         "/Script/FactoryGame.InventoryStack" => {
+          let unk_str_1 = self.read_length_prefixed_string::<E>()?;
+          let unk_str_2 = self.read_length_prefixed_string::<E>()?;
+          let unk_int_1 = self.read_i32::<E>()?;
+          let unk_int_2 = self.read_i32::<E>()?;
+          let (struct_type, struct_guid, struct_value) = self.read_struct_property::<E>(parent_type, header)?;
           FINLuaProcessorStateStorageStructValue::InventoryStack(
             InventoryStack {
-              unk_str_1: self.read_length_prefixed_string::<E>()?,
-              unk_str_2: self.read_length_prefixed_string::<E>()?,
-              unk_int_1: self.read_i32::<E>()?,
-              unk_int_2: self.read_i32::<E>()?,
-              unk_struct_1: self.read_struct_property::<E>(parent_type, header)?,
+              unk_str_1,
+              unk_str_2,
+              unk_int_1,
+              unk_int_2,
+              unk_struct_1: (struct_type, struct_value),
+              unk_struct_1_guid: Some(struct_guid),
               unk_str_3: self.read_length_prefixed_string::<E>()?,
             }
           )
@@ -611,7 +800,7 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
   }
 
   /// Reads an array property
-  fn read_array_property<E: ByteOrder>(&mut self, property_name: &String, header: &Header) -> Result<ArrayProperty> {
+  fn read_array_property<E: ByteOrder>(&mut self, header: &Header) -> Result<ArrayProperty> {
     let mut property = ArrayProperty::default();
 
     let r#type = self.read_length_prefixed_string::<E>()?;
@@ -629,22 +818,9 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
         }
       },
       "Byte" => {
-        if property_name == "mFogOfWarRawData" {
-          for _ in 0..(num_elements / 4) {
-            self.read_u8()?;
-            self.read_u8()?;
-            property.elements.push(
-              ArrayPropertyValue::Byte(self.read_u8()?)
-            );
-            self.read_u8()?;
-          }
-        } else {
-          for _ in 0..num_elements {
-            property.elements.push(
-              ArrayPropertyValue::Byte(self.read_u8()?)
-            );
-          }
-        }
+        let mut bytes = vec![0; num_elements.max(0) as usize];
+        self.read_exact(&mut bytes)?;
+        property.elements.push(ArrayPropertyValue::Bytes(bytes));
       },
       "Int" => {
         for _ in 0..num_elements {
@@ -685,13 +861,13 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
       },
       "SoftObject" => {
         for _ in 0..num_elements {
-          let unk_str_1 = self.read_length_prefixed_string::<E>()?;
-          let unk_str_2 = self.read_length_prefixed_string::<E>()?;
-          let unk_str_3 = self.read_length_prefixed_string::<E>()?;
-
-          debug!("Got 'SoftObject': {}", unk_str_1);
-          debug!("Got 'SoftObject': {}", unk_str_2);
-          debug!("Got 'SoftObject': {}", unk_str_3);
+          let soft_object = SoftObjectPath {
+            package_name: self.read_length_prefixed_string::<E>()?,
+            asset_name: self.read_length_prefixed_string::<E>()?,
+            sub_path: self.read_length_prefixed_string::<E>()?,
+          };
+          debug!("Got 'SoftObject': {:?}", soft_object);
+          property.elements.push(ArrayPropertyValue::SoftObject(soft_object));
         }
       },
       "Struct" => {
@@ -789,6 +965,7 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
             map_property.unk_float_3 = Some(Numeric::Float(self.read_f32::<E>()?));
             map_property.unk_float_4 = Some(Numeric::Float(self.read_f32::<E>()?));
             map_property.unk_str_1 = Some(self.read_length_prefixed_string::<E>()?);
+            map_property.keys.push(key);
             break;
           } else {
             let mut object = ObjectReference::default();
@@ -801,6 +978,7 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
             map_property.m_normal_index = Some(self.read_i32::<E>()?);
             map_property.m_overflow_index = Some(self.read_i32::<E>()?);
             map_property.m_filter_index = Some(self.read_i32::<E>()?);
+            map_property.keys.push(key);
             break;
           }
 
@@ -808,6 +986,7 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
             map_property.unk_float_1 = Some(Numeric::Double(self.read_f64::<E>()?));
             map_property.unk_float_2 = Some(Numeric::Double(self.read_f64::<E>()?));
             map_property.unk_float_3 = Some(Numeric::Double(self.read_f64::<E>()?));
+            map_property.keys.push(key);
             break;
           }
 
@@ -869,8 +1048,9 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
     Ok(property)
   }
 
-  /// Reads a struct property
-  fn read_struct_property<E: ByteOrder>(&mut self, parent_type: Option<&String>, header: &Header) -> Result<(String, StructPropertyValue)> {
+  /// Reads a struct property, returning its type, the 17 bytes following the
+  /// type and its value
+  fn read_struct_property<E: ByteOrder>(&mut self, parent_type: Option<&String>, header: &Header) -> Result<(String, Vec<u8>, StructPropertyValue)> {
     let parent_type = match parent_type {
       Some(t) => t,
       None => &String::from(""),
@@ -879,7 +1059,8 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
     let r#type = self.read_length_prefixed_string::<E>()?;
 
     // TODO: What is this?
-    self.seek_relative(17)?;
+    let mut guid = vec![0; 17];
+    self.read_exact(&mut guid)?;
 
     let value = match r#type.as_str() {
       "Color" => StructPropertyValue::Color(self.read_color_byte()?),
@@ -950,7 +1131,7 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
       },
     };
 
-    Ok((r#type, value))
+    Ok((r#type, guid, value))
   }
 
   /// Reads a text property
@@ -982,7 +1163,9 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
             },
             _ => return Err(ParseError::UnknownTextArgumentValueType(argument.value_type)),
           }
+          history.arguments.push(argument);
         }
+        property.value = TextPropertyHistory::ArgumentHistory(history);
       },
       10 => {
         let mut history = TransformHistory::default();
@@ -1040,9 +1223,10 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
     };
 
     let mut guid: Option<String> = None;
+    let mut struct_guid: Option<Vec<u8>> = None;
     match &mut value {
       PropertyValue::Array(p) => {
-        *p = self.read_array_property::<E>(&name, header)?;
+        *p = self.read_array_property::<E>(header)?;
       },
       PropertyValue::Bool(p) => {
         *p = self.read_u8()?;
@@ -1103,7 +1287,9 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
         *p = self.read_length_prefixed_string::<E>()?;
       },
       PropertyValue::Struct(p) => {
-        *p = self.read_struct_property::<E>(parent_type, &header)?;
+        let (struct_type, guid, value) = self.read_struct_property::<E>(parent_type, &header)?;
+        struct_guid = Some(guid);
+        *p = (struct_type, value);
       },
       PropertyValue::Text(p) => {
         guid = self.read_property_guid::<E>()?;
@@ -1125,6 +1311,7 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
       r#type,
       index,
       guid,
+      struct_guid,
       value,
     }))
   }
@@ -1144,7 +1331,7 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
     object.set_save_version(object_save_version);
 
     // TODO: What is this?
-    self.seek_relative(4)?;
+    object.set_unk_int_1(self.read_i32::<E>()?);
 
     let object_size_bytes = self.read_i32::<E>()?;
 
@@ -1218,10 +1405,8 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
           let mut item = Conveyor::default();
           item.length = self.read_i32::<E>()?;
           item.name = self.read_length_prefixed_string::<E>()?;
-
-          self.seek_length_prefixed_string::<E>()?;
-          self.seek_length_prefixed_string::<E>()?;
-
+          item.unk_str_1 = self.read_length_prefixed_string::<E>()?;
+          item.unk_str_2 = self.read_length_prefixed_string::<E>()?;
           item.position = self.read_f32::<E>()?;
           extra.elements.push(item);
         }
@@ -1240,6 +1425,7 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
           while let Some(p) = self.read_property::<E>(header, None)? {
             element.properties.push(p);
           }
+          extra.active_action.push(element);
         }
 
         let num_action_queue_elements = self.read_i32::<E>()?;
@@ -1249,6 +1435,7 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
           while let Some(p) = self.read_property::<E>(header, None)? {
             element.properties.push(p);
           }
+          extra.action_queue.push(element);
         }
 
         object.set_extra(ObjectExtra::DroneTransport(extra));
@@ -1272,6 +1459,8 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
         let mut next = ObjectReference::default();
         self.read_object_reference::<E>(&mut next, &header.map_name)?;
         extra.next = next;
+
+        object.set_extra(ObjectExtra::Locomotive(extra));
       },
       Some(ObjectHeaderType::Game) => {
         let mut extra = Extra::default();
@@ -1286,52 +1475,38 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
 
         object.set_extra(ObjectExtra::Game(extra))
       },
+      Some(ObjectHeaderType::LightweightBuildables) => {
+        let len = current_object_end_position - current_position;
+        let mut bytes = vec![0; len as usize];
+        self.read_exact(&mut bytes)?;
+
+        // The layout has changed between game versions, so anything which
+        // doesn't decode to exactly the object's size is kept as raw bytes
+        let mut cursor = io::Cursor::new(&bytes);
+        let extra = match cursor.read_lightweight_buildables::<E>() {
+          Ok(extra) if cursor.position() == len => extra,
+          _ => {
+            warn!("Couldn't decode {len} bytes of lightweight buildables, keeping them as they are");
+            LightweightBuildables { raw: Some(bytes), ..Default::default() }
+          },
+        };
+
+        object.set_extra(ObjectExtra::LightweightBuildables(extra));
+      },
       Some(ObjectHeaderType::PlayerState) => {
-        let missing_player_state = current_object_end_position - current_position;
-
-        let missing = self.read_hex::<E>(missing_player_state as usize)?;
-        object.set_missing(missing.clone());
-        self.seek_relative(-(missing_player_state as i64 * 2))?;
-
-        if missing_player_state > 0 {
-          let mut player_state = PlayerState::default();
-          player_state.count = self.read_i32::<E>()?;
-          let player_type = self.read_u8()?;
-          match player_type {
-            3 => {}, // Do nothin'!
-            8 => player_state.platform_id = Some(self.read_length_prefixed_string::<E>()?),
-            17 => player_state.eos_id = Some(self.read_platform_id::<E>()?),
-            25 | 29 => player_state.steam_id = Some(self.read_platform_id::<E>()?),
-            241 => {
-              let id_type = self.read_u8()?;
-              let len = self.read_i32::<E>()?;
-              let mut id = String::new();
-              for _ in 0..len {
-                let byte = self.read_u8()?;
-                let hex_byte = format!("{:02x}", byte);
-                id.push_str(&hex_byte)
-              }
-
-              match id_type {
-                1 => {
-                  let id = id.trim_start_matches("0")[1..33].to_string();
-                  player_state.eos_id = Some(id);
-                },
-                6 => {
-                  player_state.steam_id = Some(id);
-                },
-                _ => return Err(ParseError::UnknownPlayerIDType(id_type)),
-              }
-            },
-            248 => {
-              self.seek_length_prefixed_string::<E>()?;
-              let id = self.read_length_prefixed_string::<E>()?;
-              let id = id.split("|").next().map(|s| s.to_string());
-              player_state.eos_id = id;
-            },
-            249 => self.seek_length_prefixed_string::<E>()?, // Do nothin' except skip!
-            _ => return Err(ParseError::UnknownPlayerType(player_type)),
-          }
+        let len = current_object_end_position - current_position;
+        if len > 0 {
+          let mut bytes = vec![0; len as usize];
+          self.read_exact(&mut bytes)?;
+
+          // IDs come in several layouts, so anything which doesn't decode
+          // and write back to the same bytes is also kept as it is
+          let mut cursor = io::Cursor::new(&bytes);
+          let player_state = match cursor.read_player_state::<E>(len) {
+            Ok(state) if cursor.position() == len && state.to_bytes::<E>()? == bytes => state,
+            Ok(state) => PlayerState { raw: Some(bytes), ..state },
+            Err(_) => PlayerState { raw: Some(bytes), ..Default::default() },
+          };
 
           object.set_extra(ObjectExtra::PlayerState(player_state));
         }
@@ -1351,33 +1526,35 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
         object.set_extra(ObjectExtra::PowerLine(extra));
       },
       Some(ObjectHeaderType::Vehicle) => {
-        let mut extra = Extra::default();
-        extra.count = self.read_i32::<E>()?;
-
-        let num_elements = self.read_i32::<E>()?;
-        for _ in 0..num_elements {
-          let mut element = Vehicle::default();
-          element.name = self.read_length_prefixed_string::<E>()?;
-          element.unk_str_1 = self.read_hex::<E>(105)?;
-          extra.elements.push(element);
-        }
+        let len = current_object_end_position - current_position;
+        let mut bytes = vec![0; len as usize];
+        self.read_exact(&mut bytes)?;
+
+        // Older saves store the physics state as 32-bit floats, which are
+        // kept as raw bytes like anything else that doesn't decode exactly
+        let mut cursor = io::Cursor::new(&bytes);
+        let extra = match cursor.read_vehicle_extra::<E>() {
+          Ok(extra) if cursor.position() == len => extra,
+          _ => {
+            warn!("Couldn't decode {len} bytes of vehicle physics at {}, keeping them as they are", object_header.get_type_path());
+            VehicleExtra { raw: Some(bytes), ..Default::default() }
+          },
+        };
 
         object.set_extra(ObjectExtra::Vehicle(extra));
       },
       None => {
-        // Handles any number of missing bytes by seeking past that amount of bytes
+        // Keeps whatever is left of the object so that it's written back as
+        // it was
         let missing_bytes = current_object_end_position - current_position;
+        let mut bytes = vec![0; missing_bytes as usize];
+        self.read_exact(&mut bytes)?;
 
-        if missing_bytes > 4 {
-          if object_header.get_type_path().starts_with("/Script/FactoryGame.FG") {
-            self.seek_relative(8)?;
-          } else {
-            let skipped = self.read_hex::<E>(missing_bytes as usize)?;
-            warn!("Missing {missing_bytes} bytes at {}: {skipped}", object_header.get_type_path());
-          }
-        } else {
-          self.seek_relative(4)?;
+        if missing_bytes > 4 && !object_header.get_type_path().starts_with("/Script/FactoryGame.FG") {
+          warn!("Missing {missing_bytes} bytes at {}", object_header.get_type_path());
         }
+
+        object.set_extra(ObjectExtra::Raw(bytes));
       },
     }
 
@@ -1385,7 +1562,7 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
   }
 
   /// Reads a single level by reading its name, object headers, collectables,
-  /// objects, and seeking past a repeated set of collectables if set
+  /// objects, and the repeated set of collectables
   fn read_level<E: ByteOrder>(&mut self, level_index: i32, is_last_level: bool, header: &Header) -> Result<Level> {
     let mut level = Level::default();
 
@@ -1441,12 +1618,12 @@ pub trait ReadSaveFileBytes: ReadBytesExt + Seek {
       level.objects.push(object);
     }
 
-    // Collectables are repeated after the object list so these can be
-    // safely skipped
+    // Collectables are repeated after the object list
     let num_second_collectables = self.read_i32::<E>()?;
     for _ in 0..num_second_collectables {
-      self.seek_length_prefixed_string::<E>()?;
-      self.seek_length_prefixed_string::<E>()?;
+      let mut collectable = Collectable::default();
+      self.read_object_reference::<E>(&mut collectable, &header.map_name)?;
+      level.second_collectables.push(collectable);
     }
 
     Ok(level)
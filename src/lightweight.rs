@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+
+use crate::region::Region;
+use crate::save::*;
+
+pub(crate) const SUBSYSTEM_CLASS: &str = "FGLightweightBuildableSubsystem";
+
+impl Transform {
+  /// The translation narrowed to the precision used by actor positions
+  pub fn position(&self) -> Vector<f32> {
+    Vector {
+      x: self.translation.x as f32,
+      y: self.translation.y as f32,
+      z: self.translation.z as f32,
+    }
+  }
+}
+
+/// Compares a class reference's path against either a full path or just
+/// the class name, e.g. `Build_Foundation_8x4_01_C`
+fn is_class(class: &LightweightBuildableClass, name: &str) -> bool {
+  class.class.path_name == name || class_name(&class.class.path_name) == name
+}
+
+impl LightweightBuildables {
+  /// Whether the instances were decoded; if not, they're only kept as raw
+  /// bytes and every other method sees no instances
+  pub fn is_decoded(&self) -> bool {
+    self.raw.is_none()
+  }
+
+  /// Total number of instances across all classes
+  pub fn len(&self) -> usize {
+    self.classes.iter().map(|c| c.instances.len()).sum()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Number of instances of each class, keyed by class name
+  pub fn counts(&self) -> BTreeMap<String, usize> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for class in &self.classes {
+      *counts.entry(class_name(&class.class.path_name).to_string()).or_default() += class.instances.len();
+    }
+    counts
+  }
+
+  /// Instances of a class, given its path or class name
+  pub fn instances<'a>(&'a self, class: &'a str) -> impl Iterator<Item = &'a LightweightInstance> {
+    self.classes.iter()
+      .filter(move |c| is_class(c, class))
+      .flat_map(|c| &c.instances)
+  }
+
+  pub fn instances_mut<'a>(&'a mut self, class: &'a str) -> impl Iterator<Item = &'a mut LightweightInstance> {
+    self.classes.iter_mut()
+      .filter(move |c| is_class(c, class))
+      .flat_map(|c| &mut c.instances)
+  }
+
+  /// Every instance along with the path of its class
+  pub fn iter(&self) -> impl Iterator<Item = (&str, &LightweightInstance)> {
+    self.classes.iter().flat_map(|c| c.instances.iter().map(move |i| (c.class.path_name.as_str(), i)))
+  }
+
+  pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut LightweightInstance)> {
+    self.classes.iter_mut().flat_map(|c| {
+      let path = c.class.path_name.as_str();
+      c.instances.iter_mut().map(move |i| (path, i))
+    })
+  }
+
+  /// Instances whose position lies within a region
+  pub fn in_region<'a>(&'a self, region: &'a Region) -> impl Iterator<Item = (&'a str, &'a LightweightInstance)> {
+    self.iter().filter(|(_, i)| region.contains(&i.transform.position()))
+  }
+
+  /// Adds an instance of the class with the given full path, e.g.
+  /// `/Game/FactoryGame/Buildable/Building/Foundation/Build_Foundation_8x4_01.Build_Foundation_8x4_01_C`
+  pub fn add(&mut self, class_path: &str, instance: LightweightInstance) {
+    match self.classes.iter_mut().find(|c| c.class.path_name == class_path) {
+      Some(class) => class.instances.push(instance),
+      None => self.classes.push(LightweightBuildableClass {
+        class: ObjectReference {
+          level_name: String::new(),
+          path_name: class_path.to_string(),
+          level_is_map: false,
+        },
+        instances: vec![instance],
+      }),
+    }
+  }
+
+  /// Keeps only the instances for which `f` returns true, given the path of
+  /// their class, and returns how many were removed. Classes left without
+  /// instances are removed too
+  pub fn retain<F: FnMut(&str, &LightweightInstance) -> bool>(&mut self, mut f: F) -> usize {
+    let before = self.len();
+    for class in &mut self.classes {
+      let path = &class.class.path_name;
+      class.instances.retain(|i| f(path, i));
+    }
+    self.classes.retain(|c| !c.instances.is_empty());
+    before - self.len()
+  }
+
+  /// Moves every instance within a region by an offset
+  pub fn translate_region(&mut self, region: &Region, offset: &Vector<f64>) -> usize {
+    let mut moved = 0;
+    for (_, instance) in self.iter_mut() {
+      if region.contains(&instance.transform.position()) {
+        let translation = &mut instance.transform.translation;
+        translation.x += offset.x;
+        translation.y += offset.y;
+        translation.z += offset.z;
+        moved += 1;
+      }
+    }
+    moved
+  }
+}
+
+impl Save {
+  /// Returns the instances held by the lightweight buildable subsystem, if
+  /// the save has one
+  pub fn lightweight_buildables(&self) -> Option<&LightweightBuildables> {
+    self.iter_objects()
+      .filter(|(object_header, _)| object_header.get_class_name() == SUBSYSTEM_CLASS)
+      .find_map(|(_, object)| match object.get_extra() {
+        Some(ObjectExtra::LightweightBuildables(e)) => Some(e),
+        _ => None,
+      })
+  }
+
+  pub fn lightweight_buildables_mut(&mut self) -> Option<&mut LightweightBuildables> {
+    self.levels.iter_mut()
+      .flat_map(|level| level.object_headers.iter().zip(level.objects.iter_mut()))
+      .filter(|(object_header, _)| object_header.get_class_name() == SUBSYSTEM_CLASS)
+      .find_map(|(_, object)| match object.get_extra_mut() {
+        Some(ObjectExtra::LightweightBuildables(e)) => Some(e),
+        _ => None,
+      })
+  }
+}
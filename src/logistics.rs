@@ -0,0 +1,255 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Serialize, Deserialize};
+
+use crate::graph::Graph;
+use crate::property::*;
+use crate::save::*;
+use crate::spline::Spline;
+
+const FACTORY_CONNECTION_PATH: &str = "/Script/FactoryGame.FGFactoryConnectionComponent";
+
+/// Items per minute of belts and lifts by tier, Mk1 to Mk6
+pub const CONVEYOR_CAPACITIES: [f32; 6] = [60.0, 120.0, 270.0, 480.0, 780.0, 1200.0];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogisticsNodeKind {
+  Belt,
+  Lift,
+  Splitter,
+  Merger,
+  Storage,
+  /// Anything else with conveyor connections, e.g. manufacturers, miners or
+  /// train stations
+  Machine,
+}
+
+impl LogisticsNodeKind {
+  fn from_class_name(class_name: &str) -> LogisticsNodeKind {
+    if class_name.starts_with("Build_ConveyorBelt") {
+      LogisticsNodeKind::Belt
+    } else if class_name.starts_with("Build_ConveyorLift") {
+      LogisticsNodeKind::Lift
+    } else if class_name.starts_with("Build_ConveyorAttachmentSplitter") {
+      LogisticsNodeKind::Splitter
+    } else if class_name.starts_with("Build_ConveyorAttachmentMerger") {
+      LogisticsNodeKind::Merger
+    } else if class_name.contains("Storage") && !class_name.contains("PowerStorage") {
+      LogisticsNodeKind::Storage
+    } else {
+      LogisticsNodeKind::Machine
+    }
+  }
+
+  pub fn is_conveyor(&self) -> bool {
+    matches!(self, LogisticsNodeKind::Belt | LogisticsNodeKind::Lift)
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LogisticsNode {
+  pub instance_name: String,
+  pub class_name: String,
+  pub kind: LogisticsNodeKind,
+  /// Mark of a belt or lift, from 1 to 6
+  pub tier: Option<u8>,
+  /// Items per minute of a belt or lift
+  pub capacity: Option<f32>,
+  /// Number of each item currently on a belt or lift
+  pub items: BTreeMap<String, usize>,
+  /// Length of a belt along its curve, in world units
+  pub length: Option<f64>,
+}
+
+/// A link between two connection components, from an output to an input
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LogisticsEdge {
+  pub from_connection: String,
+  pub to_connection: String,
+  /// Tier of the slowest belt or lift at either end
+  pub tier: Option<u8>,
+  pub capacity: Option<f32>,
+}
+
+/// A belt, lift, splitter or merger which can receive more items per minute
+/// than it can pass on
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bottleneck {
+  pub instance_name: String,
+  pub input_capacity: f32,
+  pub output_capacity: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Direction {
+  Input,
+  Output,
+}
+
+/// The game doesn't save the direction of a connection, but it follows from
+/// the component's name: belts and lifts go from `ConveyorAny0` to
+/// `ConveyorAny1` and everything else names them `Input*` or `Output*`
+fn direction(connection_name: &str) -> Option<Direction> {
+  let name = connection_name.rsplit('.').next().unwrap_or_default();
+  if name.starts_with("Input") || name == "ConveyorAny0" {
+    Some(Direction::Input)
+  } else if name.starts_with("Output") || name == "ConveyorAny1" {
+    Some(Direction::Output)
+  } else {
+    None
+  }
+}
+
+/// Reads the mark from class names such as `Build_ConveyorBeltMk3_C`
+fn conveyor_tier(class_name: &str) -> Option<u8> {
+  let mark = class_name.split("Mk").nth(1)?;
+  let tier: u8 = mark.trim_end_matches("_C").parse().ok()?;
+  (1..=CONVEYOR_CAPACITIES.len() as u8).contains(&tier).then_some(tier)
+}
+
+/// The conveyor network of a save as a directed graph following the flow of
+/// items
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LogisticsGraph {
+  pub graph: Graph<LogisticsNode, LogisticsEdge>,
+}
+
+impl LogisticsGraph {
+  pub fn from_save(save: &Save) -> LogisticsGraph {
+    let mut graph: Graph<LogisticsNode, LogisticsEdge> = Graph::default();
+    let index = save.index();
+
+    let mut connected: Vec<(&ComponentHeader, &String)> = vec![];
+    for (object_header, object) in save.iter_objects() {
+      let ObjectHeader::Component(c) = object_header else {
+        continue;
+      };
+      if c.type_path != FACTORY_CONNECTION_PATH {
+        continue;
+      }
+      if let Some(PropertyValue::Object(o)) = find_property(object.get_properties(), "mConnectedComponent").map(|p| &p.value) {
+        if !o.path_name.is_empty() {
+          connected.push((c, &o.path_name));
+        }
+      }
+    }
+
+    // Only buildings with at least one connection in use become nodes
+    let parents: HashMap<&String, &String> = save.iter_objects()
+      .filter_map(|(object_header, _)| match object_header {
+        ObjectHeader::Component(c) if c.type_path == FACTORY_CONNECTION_PATH => Some((&c.instance_name, &c.parent_actor_name)),
+        _ => None,
+      })
+      .collect();
+    for (c, _) in &connected {
+      let Some((ObjectHeader::Actor(actor), object)) = index.find_object(&c.parent_actor_name) else {
+        continue;
+      };
+      let class = class_name(&actor.type_path);
+      let kind = LogisticsNodeKind::from_class_name(class);
+      let tier = if kind.is_conveyor() { conveyor_tier(class) } else { None };
+
+      let mut items: BTreeMap<String, usize> = BTreeMap::new();
+      if let Some(ObjectExtra::Conveyor(conveyor)) = object.get_extra() {
+        for item in &conveyor.elements {
+          *items.entry(item.name.clone()).or_default() += 1;
+        }
+      }
+
+      graph.add_node(&actor.instance_name, LogisticsNode {
+        instance_name: actor.instance_name.clone(),
+        class_name: class.to_string(),
+        kind,
+        tier,
+        capacity: tier.map(|t| CONVEYOR_CAPACITIES[t as usize - 1]),
+        items,
+        length: match kind {
+          LogisticsNodeKind::Belt => Spline::from_properties(object.get_properties()).map(|s| s.length()),
+          _ => None,
+        },
+      });
+    }
+
+    // Both ends of a link reference each other, so each link is only added
+    // from its output side (or from either side when neither is known)
+    for (c, other) in &connected {
+      let Some(other_parent) = parents.get(other) else {
+        continue;
+      };
+      let (Some(a), Some(b)) = (graph.index_of(&c.parent_actor_name), graph.index_of(other_parent)) else {
+        continue;
+      };
+
+      let (from, to, from_connection, to_connection) = match (direction(&c.instance_name), direction(other)) {
+        (Some(Direction::Output), _) | (None, Some(Direction::Input)) => (a, b, &c.instance_name, *other),
+        (None, None) if c.instance_name < **other => (a, b, &c.instance_name, *other),
+        _ => continue,
+      };
+
+      let tier = [graph.nodes[from].tier, graph.nodes[to].tier].into_iter().flatten().min();
+      graph.add_edge(from, to, LogisticsEdge {
+        from_connection: from_connection.clone(),
+        to_connection: to_connection.clone(),
+        tier,
+        capacity: tier.map(|t| CONVEYOR_CAPACITIES[t as usize - 1]),
+      });
+    }
+
+    LogisticsGraph { graph }
+  }
+
+  pub fn node(&self, instance_name: &str) -> Option<&LogisticsNode> {
+    self.graph.node(instance_name)
+  }
+
+  /// Every building items can flow to from the given one
+  pub fn downstream(&self, instance_name: &str) -> Vec<&LogisticsNode> {
+    self.trace(instance_name, true)
+  }
+
+  /// Every building items can flow from to reach the given one
+  pub fn upstream(&self, instance_name: &str) -> Vec<&LogisticsNode> {
+    self.trace(instance_name, false)
+  }
+
+  fn trace(&self, instance_name: &str, forwards: bool) -> Vec<&LogisticsNode> {
+    match self.graph.index_of(instance_name) {
+      Some(start) => self.graph.reachable(start, forwards).into_iter().map(|i| &self.graph.nodes[i]).collect(),
+      None => vec![],
+    }
+  }
+
+  /// Finds belts, lifts, splitters and mergers whose inputs can carry more
+  /// than their outputs, e.g. a Mk5 belt feeding a Mk3 belt or two full
+  /// belts merged onto one of the same tier. Only links whose capacity is
+  /// known on both sides are compared
+  pub fn bottlenecks(&self) -> Vec<Bottleneck> {
+    let mut bottlenecks: Vec<Bottleneck> = vec![];
+
+    for (i, node) in self.graph.nodes.iter().enumerate() {
+      if node.kind == LogisticsNodeKind::Machine || node.kind == LogisticsNodeKind::Storage {
+        continue;
+      }
+
+      let capacity_of = |j: usize| self.graph.nodes[j].capacity;
+      let inputs: Option<Vec<f32>> = self.graph.incoming(i).map(|e| capacity_of(e.from)).collect();
+      let output_capacity = match node.capacity {
+        Some(capacity) => Some(capacity),
+        None => self.graph.outgoing(i).map(|e| capacity_of(e.to)).sum::<Option<f32>>(),
+      };
+
+      if let (Some(inputs), Some(output_capacity)) = (inputs, output_capacity) {
+        let input_capacity: f32 = inputs.iter().sum();
+        if !inputs.is_empty() && input_capacity > output_capacity {
+          bottlenecks.push(Bottleneck {
+            instance_name: node.instance_name.clone(),
+            input_capacity,
+            output_capacity,
+          });
+        }
+      }
+    }
+
+    bottlenecks
+  }
+}
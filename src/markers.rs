@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use crate::errors::ParseError;
+use crate::property::*;
+use crate::save::*;
+use crate::Result;
+
+const MAP_MANAGER_CLASS: &str = "BP_MapManager_C";
+const MAP_MARKERS_PROPERTY: &str = "mMapMarkers";
+const MAP_MARKER_STRUCT: &str = "MapMarker";
+const COMPASS_VIEW_DISTANCE_ENUM: &str = "ECompassViewDistance";
+const MARKER_TYPE_ENUM: &str = "ERepresentationType";
+
+/// A marker placed on the map by a player
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MapMarker {
+  pub id: Option<i32>,
+  pub name: String,
+  pub location: Vector<f64>,
+  pub icon_id: i32,
+  pub color: Option<Color<f32>>,
+  pub category: String,
+  pub scale: f32,
+  /// How far away the marker shows on the compass, e.g.
+  /// `ECompassViewDistance::CVD_Always`
+  pub compass_view_distance: Option<String>,
+  /// Whether this is a marker or a stamp, e.g. `ERepresentationType::RT_Stamp`
+  pub marker_type: Option<String>,
+  /// Account ID of the player who placed the marker
+  pub placed_by: Option<String>,
+}
+
+impl Default for MapMarker {
+  fn default() -> MapMarker {
+    MapMarker {
+      id: None,
+      name: String::new(),
+      location: Vector::default(),
+      icon_id: 0,
+      color: None,
+      category: String::new(),
+      scale: 1.0,
+      compass_view_distance: None,
+      marker_type: None,
+      placed_by: None,
+    }
+  }
+}
+
+/// Sets an enum, keeping the kind of property it's already stored as
+fn set_enum(properties: &mut Vec<Property>, name: &str, enum_type: &str, value: &str) {
+  match find_property_mut(properties, name).map(|p| &mut p.value) {
+    Some(PropertyValue::Byte(b)) => b.string_value = Some(value.to_string()),
+    Some(PropertyValue::Enum(e)) => {
+      for v in e.values_mut() {
+        *v = value.to_string();
+      }
+    },
+    _ => set_property_value(properties, name, PropertyValue::Byte(ByteProperty {
+      r#type: enum_type.to_string(),
+      byte_value: None,
+      string_value: Some(value.to_string()),
+    })),
+  }
+}
+
+impl MapMarker {
+  pub fn new(name: &str, location: Vector<f64>) -> MapMarker {
+    MapMarker {
+      name: name.to_string(),
+      location,
+      ..Default::default()
+    }
+  }
+
+  /// Reads a marker from the properties of a `MapMarker` struct
+  pub fn from_properties(properties: &[Property]) -> MapMarker {
+    let string = |name: &str| match find_property(properties, name).map(|p| &p.value) {
+      Some(PropertyValue::String(s)) => Some(s.clone()),
+      _ => None,
+    };
+
+    MapMarker {
+      id: match find_property(properties, "MarkerID").map(|p| &p.value) {
+        Some(PropertyValue::Int(i)) => Some(*i),
+        Some(PropertyValue::Byte(b)) => b.byte_value.map(|b| b as i32),
+        _ => None,
+      },
+      name: string("Name").unwrap_or_default(),
+      location: match find_property(properties, "Location").map(|p| &p.value) {
+        Some(PropertyValue::Struct((_, StructPropertyValue::DoubleVector(v)))) => v.clone(),
+        _ => Vector::default(),
+      },
+      icon_id: property_int(properties, "IconID").unwrap_or(0),
+      color: match find_property(properties, "Color").map(|p| &p.value) {
+        Some(PropertyValue::Struct((_, StructPropertyValue::LinearColor(c)))) => Some(c.clone()),
+        _ => None,
+      },
+      category: string("CategoryName").unwrap_or_default(),
+      scale: property_f32(properties, "Scale").unwrap_or(1.0),
+      compass_view_distance: property_enum(properties, "CompassViewDistance").cloned(),
+      marker_type: property_enum(properties, "MapMarkerType").cloned(),
+      placed_by: string("MarkerPlacedByAccountID").filter(|s| !s.is_empty()),
+    }
+  }
+
+  /// Updates the properties of a `MapMarker` struct, leaving any others as
+  /// they are. Fails without changing anything if the ID is stored as a
+  /// byte and doesn't fit in one
+  pub fn apply_to(&self, properties: &mut Vec<Property>) -> Result<()> {
+    if let Some(id) = self.id {
+      match find_property_mut(properties, "MarkerID").map(|p| &mut p.value) {
+        Some(PropertyValue::Byte(b)) => b.byte_value = Some(u8::try_from(id).map_err(|_| ParseError::MarkerIdOutOfRange(id))?),
+        _ => set_property_value(properties, "MarkerID", PropertyValue::Int(id)),
+      }
+    }
+    set_property_value(properties, "Location", PropertyValue::Struct(("Vector".to_string(), StructPropertyValue::DoubleVector(self.location.clone()))));
+    set_property_value(properties, "Name", PropertyValue::String(self.name.clone()));
+    set_property_value(properties, "CategoryName", PropertyValue::String(self.category.clone()));
+    set_property_value(properties, "IconID", PropertyValue::Int(self.icon_id));
+    match &self.color {
+      Some(color) => set_property_value(properties, "Color", PropertyValue::Struct(("LinearColor".to_string(), StructPropertyValue::LinearColor(color.clone())))),
+      None => properties.retain(|p| p.name != "Color"),
+    }
+    set_property_value(properties, "Scale", PropertyValue::Float(self.scale));
+    if let Some(distance) = &self.compass_view_distance {
+      set_enum(properties, "CompassViewDistance", COMPASS_VIEW_DISTANCE_ENUM, distance);
+    }
+    if let Some(marker_type) = &self.marker_type {
+      set_enum(properties, "MapMarkerType", MARKER_TYPE_ENUM, marker_type);
+    }
+    set_property_value(properties, "MarkerPlacedByAccountID", PropertyValue::String(self.placed_by.clone().unwrap_or_default()));
+    Ok(())
+  }
+}
+
+#[derive(Serialize, Deserialize)]
+struct FeatureCollection {
+  #[serde(rename = "type")]
+  kind: String,
+  features: Vec<Feature>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Feature {
+  #[serde(rename = "type")]
+  kind: String,
+  geometry: Geometry,
+  #[serde(default)]
+  properties: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Geometry {
+  #[serde(rename = "type")]
+  kind: String,
+  coordinates: Value,
+}
+
+/// Writes markers as a GeoJSON feature collection of points. Coordinates
+/// are the game's world coordinates in centimetres as `[x, y, z]`, with Y
+/// pointing south as on the in-game map
+pub fn markers_to_geojson(markers: &[MapMarker]) -> Result<String> {
+  let features = markers.iter()
+    .map(|marker| {
+      let mut properties: HashMap<String, Value> = HashMap::new();
+      properties.insert("name".to_string(), Value::from(marker.name.clone()));
+      properties.insert("category".to_string(), Value::from(marker.category.clone()));
+      properties.insert("icon_id".to_string(), Value::from(marker.icon_id));
+      properties.insert("scale".to_string(), Value::from(marker.scale));
+      if let Some(c) = &marker.color {
+        properties.insert("color".to_string(), Value::from(vec![c.red, c.green, c.blue, c.alpha]));
+      }
+      if let Some(distance) = &marker.compass_view_distance {
+        properties.insert("compass_view_distance".to_string(), Value::from(distance.clone()));
+      }
+      if let Some(marker_type) = &marker.marker_type {
+        properties.insert("marker_type".to_string(), Value::from(marker_type.clone()));
+      }
+
+      Feature {
+        kind: "Feature".to_string(),
+        geometry: Geometry {
+          kind: "Point".to_string(),
+          coordinates: Value::from(vec![marker.location.x, marker.location.y, marker.location.z]),
+        },
+        properties,
+      }
+    })
+    .collect();
+
+  Ok(serde_json::to_string(&FeatureCollection {
+    kind: "FeatureCollection".to_string(),
+    features,
+  })?)
+}
+
+/// Reads markers from the points of a GeoJSON feature collection in the
+/// coordinates written by `markers_to_geojson`; other geometries are
+/// skipped. The markers have no ID, so adding them to a save assigns new
+/// ones
+pub fn markers_from_geojson(json: &str) -> Result<Vec<MapMarker>> {
+  let collection: FeatureCollection = serde_json::from_str(json)?;
+
+  Ok(collection.features.iter()
+    .filter(|f| f.geometry.kind == "Point")
+    .filter_map(|f| {
+      let coordinates: Vec<f64> = serde_json::from_value(f.geometry.coordinates.clone()).ok()?;
+      let string = |name: &str| f.properties.get(name).and_then(|v| v.as_str()).map(|s| s.to_string());
+      let number = |name: &str| f.properties.get(name).and_then(|v| v.as_f64());
+
+      Some(MapMarker {
+        name: string("name").unwrap_or_default(),
+        location: Vector {
+          x: *coordinates.first()?,
+          y: *coordinates.get(1)?,
+          z: coordinates.get(2).copied().unwrap_or_default(),
+        },
+        icon_id: number("icon_id").unwrap_or_default() as i32,
+        color: f.properties.get("color")
+          .and_then(|v| serde_json::from_value::<[f32; 4]>(v.clone()).ok())
+          .map(|[red, green, blue, alpha]| Color { red, green, blue, alpha }),
+        category: string("category").unwrap_or_default(),
+        scale: number("scale").unwrap_or(1.0) as f32,
+        compass_view_distance: string("compass_view_distance"),
+        marker_type: string("marker_type"),
+        ..Default::default()
+      })
+    })
+    .collect())
+}
+
+impl Save {
+  /// The map manager, which holds the markers and the fog of war
+  pub(crate) fn map_manager(&self) -> Option<&Object> {
+    self.iter_objects()
+      .find(|(object_header, _)| object_header.get_class_name() == MAP_MANAGER_CLASS)
+      .map(|(_, object)| object)
+  }
+
+  pub(crate) fn map_manager_mut(&mut self) -> Result<&mut Object> {
+    self.levels.iter_mut()
+      .flat_map(|level| level.object_headers.iter().zip(level.objects.iter_mut()))
+      .find(|(object_header, _)| object_header.get_class_name() == MAP_MANAGER_CLASS)
+      .map(|(_, object)| object)
+      .ok_or_else(|| ParseError::MissingObject(MAP_MANAGER_CLASS.to_string()))
+  }
+
+  /// The markers array, or `None` if the map manager has no markers yet
+  fn existing_map_marker_elements_mut(&mut self) -> Result<Option<&mut Vec<ArrayPropertyValue>>> {
+    let properties = self.map_manager_mut()?.get_properties_mut();
+    match find_property_mut(properties, MAP_MARKERS_PROPERTY).map(|p| &mut p.value) {
+      Some(PropertyValue::Array(a)) => Ok(Some(&mut a.elements)),
+      Some(_) => Err(ParseError::MissingObject(MAP_MARKERS_PROPERTY.to_string())),
+      None => Ok(None),
+    }
+  }
+
+  /// The markers array, added to the map manager if it has none yet
+  fn map_marker_elements_mut(&mut self) -> Result<&mut Vec<ArrayPropertyValue>> {
+    let properties = self.map_manager_mut()?.get_properties_mut();
+    if find_property(properties, MAP_MARKERS_PROPERTY).is_none() {
+      properties.push(Property::new(MAP_MARKERS_PROPERTY, PropertyValue::Array(ArrayProperty {
+        r#type: "Struct".to_string(),
+        struct_meta: Some(ArrayPropertyStruct {
+          r#type: MAP_MARKER_STRUCT.to_string(),
+          ..Default::default()
+        }),
+        elements: vec![],
+      })));
+    }
+
+    match find_property_mut(properties, MAP_MARKERS_PROPERTY).map(|p| &mut p.value) {
+      Some(PropertyValue::Array(a)) => Ok(&mut a.elements),
+      _ => Err(ParseError::MissingObject(MAP_MARKERS_PROPERTY.to_string())),
+    }
+  }
+
+  /// Returns every marker on the map
+  pub fn map_markers(&self) -> Vec<MapMarker> {
+    let Some(object) = self.map_manager() else {
+      return vec![];
+    };
+
+    match find_property(object.get_properties(), MAP_MARKERS_PROPERTY).map(|p| &p.value) {
+      Some(PropertyValue::Array(a)) => a.elements.iter()
+        .filter_map(|e| match e {
+          ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(properties)) => Some(MapMarker::from_properties(properties)),
+          _ => None,
+        })
+        .collect(),
+      _ => vec![],
+    }
+  }
+
+  pub fn map_marker(&self, id: i32) -> Option<MapMarker> {
+    self.map_markers().into_iter().find(|m| m.id == Some(id))
+  }
+
+  /// Adds a marker, giving it the next free ID if it has none, and returns
+  /// its ID. Fails if another marker already has its ID. New markers copy
+  /// the properties of an existing one so that fields this library doesn't
+  /// know about are kept, less the optional ones the marker leaves unset.
+  /// Saves which number markers with a byte can't take an ID past 255
+  pub fn add_map_marker(&mut self, marker: &MapMarker) -> Result<i32> {
+    let elements = self.map_marker_elements_mut()?;
+
+    let mut template: Option<Vec<Property>> = None;
+    let mut next_id = 0;
+    for element in elements.iter() {
+      if let ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(properties)) = element {
+        if let Some(id) = MapMarker::from_properties(properties).id {
+          if marker.id == Some(id) {
+            return Err(ParseError::DuplicateMarkerId(id));
+          }
+          next_id = next_id.max(id + 1);
+        }
+        template.get_or_insert_with(|| properties.clone());
+      }
+    }
+
+    let id = marker.id.unwrap_or(next_id);
+    let mut properties = template.unwrap_or_default();
+    // The template's optional fields aren't the new marker's, and leaving
+    // them out gives the game's defaults
+    if marker.compass_view_distance.is_none() {
+      properties.retain(|p| p.name != "CompassViewDistance");
+    }
+    if marker.marker_type.is_none() {
+      properties.retain(|p| p.name != "MapMarkerType");
+    }
+    MapMarker { id: Some(id), ..marker.clone() }.apply_to(&mut properties)?;
+    elements.push(ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(properties)));
+
+    Ok(id)
+  }
+
+  /// Replaces the marker with the given ID. Returns whether it exists
+  pub fn update_map_marker(&mut self, id: i32, marker: &MapMarker) -> Result<bool> {
+    let Some(elements) = self.existing_map_marker_elements_mut()? else {
+      return Ok(false);
+    };
+    for element in elements {
+      if let ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(properties)) = element {
+        if MapMarker::from_properties(properties).id == Some(id) {
+          MapMarker { id: Some(id), ..marker.clone() }.apply_to(properties)?;
+          return Ok(true);
+        }
+      }
+    }
+    Ok(false)
+  }
+
+  /// Removes the marker with the given ID. Returns whether it existed
+  pub fn remove_map_marker(&mut self, id: i32) -> Result<bool> {
+    let Some(elements) = self.existing_map_marker_elements_mut()? else {
+      return Ok(false);
+    };
+    let len = elements.len();
+    elements.retain(|e| match e {
+      ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(properties)) => MapMarker::from_properties(properties).id != Some(id),
+      _ => true,
+    });
+    Ok(elements.len() != len)
+  }
+
+  /// Adds markers, e.g. read with `markers_from_geojson`, and returns their
+  /// IDs
+  pub fn import_map_markers(&mut self, markers: &[MapMarker]) -> Result<Vec<i32>> {
+    markers.iter()
+      .map(|marker| self.add_map_marker(&MapMarker { id: None, ..marker.clone() }))
+      .collect()
+  }
+}
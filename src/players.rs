@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::inventory::Inventory;
+use crate::markers::MapMarker;
+use crate::property::*;
+use crate::save::*;
+
+const PLAYER_STATE_PATH: &str = "/Game/FactoryGame/Character/Player/BP_PlayerState.BP_PlayerState_C";
+const SHOPPING_LIST_PATH: &str = "ShoppingListComponent";
+
+/// Properties of the player pawn holding equipment inventories, keyed by
+/// the name they're reported under
+const EQUIPMENT_SLOTS: [(&str, &str); 6] = [
+  ("arm", "mArmSlot"),
+  ("back", "mBackSlot"),
+  ("head", "mHeadSlot"),
+  ("body", "mBodySlot"),
+  ("legs", "mLegsSlot"),
+  ("heart", "mHeartSlot"),
+];
+
+/// Health of a player pawn when the save doesn't record it
+const DEFAULT_HEALTH: f32 = 100.0;
+
+/// A player, combining their `BP_PlayerState` actor with the `Char_Player`
+/// pawn they control. Players who never spawned have no pawn
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Player {
+  pub state_name: String,
+  pub pawn_name: Option<String>,
+  pub name: Option<String>,
+  pub id: Option<PlayerId>,
+  pub position: Option<Vector<f32>>,
+  pub rotation: Option<Quaternion<f32>>,
+  pub health: f32,
+  pub inventory: Option<Inventory>,
+  pub equipment: BTreeMap<String, Inventory>,
+  /// Each hotbar's slots, holding the path of the recipe, emote or
+  /// blueprint bound to it
+  pub hotbars: Vec<Vec<Option<String>>>,
+  /// Recipes on the shopping list with how many of each
+  pub shopping_list: Vec<(String, i32)>,
+  pub map_markers: Vec<MapMarker>,
+}
+
+impl Save {
+  /// Returns every player in the save
+  pub fn players(&self) -> Vec<Player> {
+    let markers = self.map_markers();
+    let index = self.index();
+
+    self.iter_objects()
+      .filter(|(object_header, _)| object_header.get_type_path() == PLAYER_STATE_PATH)
+      .map(|(object_header, object)| Self::read_player(&index, object_header.get_instance_name(), object, &markers))
+      .collect()
+  }
+
+  fn read_player(index: &SaveIndex, state_name: &str, state: &Object, markers: &[MapMarker]) -> Player {
+    let properties = state.get_properties();
+
+    let mut player = Player {
+      state_name: state_name.to_string(),
+      pawn_name: property_reference(properties, "mOwnedPawn").cloned(),
+      name: match find_property(properties, "mCachedPlayerName").map(|p| &p.value) {
+        Some(PropertyValue::String(s)) => Some(s.clone()),
+        _ => None,
+      },
+      id: match state.get_extra() {
+        Some(ObjectExtra::PlayerState(p)) => p.id.clone(),
+        _ => None,
+      },
+      position: None,
+      rotation: None,
+      health: DEFAULT_HEALTH,
+      inventory: None,
+      equipment: BTreeMap::new(),
+      hotbars: Self::read_hotbars(index, properties),
+      shopping_list: Self::read_shopping_list(index, state),
+      map_markers: vec![],
+    };
+
+    let account_ids: Vec<String> = match &player.id {
+      Some(PlayerId::Steam(id)) => vec![id.to_string()],
+      Some(PlayerId::Epic { product_user_id, account_id }) => account_id.iter().cloned().chain([product_user_id.clone()]).collect(),
+      Some(PlayerId::Platform(id)) => vec![id.clone()],
+      _ => vec![],
+    };
+    player.map_markers = markers.iter()
+      .filter(|m| m.placed_by.as_ref().is_some_and(|id| account_ids.contains(id)))
+      .cloned()
+      .collect();
+
+    let pawn = player.pawn_name.as_ref().and_then(|name| index.find_object(name));
+    if let Some((ObjectHeader::Actor(actor), pawn)) = pawn {
+      let properties = pawn.get_properties();
+      player.position = Some(actor.position.clone());
+      player.rotation = Some(actor.rotation.clone());
+      player.inventory = property_reference(properties, "mInventory").and_then(|name| index.inventory(name));
+      for (slot, property) in EQUIPMENT_SLOTS {
+        if let Some(inventory) = property_reference(properties, property).and_then(|name| index.inventory(name)) {
+          player.equipment.insert(slot.to_string(), inventory);
+        }
+      }
+
+      let health = property_reference(properties, "mHealthComponent").and_then(|name| index.find_object(name));
+      if let Some((_, health)) = health {
+        if let Some(PropertyValue::Float(f)) = find_property(health.get_properties(), "mCurrentHealth").map(|p| &p.value) {
+          player.health = *f;
+        }
+      }
+    }
+
+    player
+  }
+
+  /// Hotbars hold references to shortcut objects, which in turn reference
+  /// what's bound to the slot
+  fn read_hotbars(index: &SaveIndex, properties: &[Property]) -> Vec<Vec<Option<String>>> {
+    let Some(PropertyValue::Array(hotbars)) = find_property(properties, "mHotbars").map(|p| &p.value) else {
+      return vec![];
+    };
+
+    hotbars.elements.iter()
+      .filter_map(|hotbar| match hotbar {
+        ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(properties)) => Some(properties),
+        _ => None,
+      })
+      .map(|properties| match find_property(properties, "HotbarShortcuts").map(|p| &p.value) {
+        Some(PropertyValue::Array(shortcuts)) => shortcuts.elements.iter()
+          .map(|shortcut| match shortcut {
+            ArrayPropertyValue::Object(o) => Self::read_shortcut(index, &o.path_name),
+            _ => None,
+          })
+          .collect(),
+        _ => vec![],
+      })
+      .collect()
+  }
+
+  fn read_shortcut(index: &SaveIndex, instance_name: &str) -> Option<String> {
+    let (_, shortcut) = index.find_object(instance_name)?;
+    let properties = shortcut.get_properties();
+
+    property_reference(properties, "mRecipeToActivate")
+      .or_else(|| property_reference(properties, "mEmoteToActivate"))
+      .cloned()
+      .or_else(|| match find_property(properties, "mBlueprintName").map(|p| &p.value) {
+        Some(PropertyValue::String(s)) => Some(s.clone()),
+        _ => None,
+      })
+  }
+
+  fn read_shopping_list(index: &SaveIndex, state: &Object) -> Vec<(String, i32)> {
+    let Object::Actor(actor) = state else {
+      return vec![];
+    };
+
+    let component = actor.components.iter()
+      .filter_map(|c| index.find_object(&c.path_name))
+      .find(|(object_header, _)| object_header.get_type_path().ends_with(SHOPPING_LIST_PATH));
+    let Some((_, component)) = component else {
+      return vec![];
+    };
+
+    let Some(PropertyValue::Array(entries)) = find_property(component.get_properties(), "mShoppingList").map(|p| &p.value) else {
+      return vec![];
+    };
+
+    // Entries are a recipe and an amount, whatever their property names
+    entries.elements.iter()
+      .filter_map(|entry| match entry {
+        ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(properties)) => {
+          let recipe = properties.iter().find_map(|p| match &p.value {
+            PropertyValue::Object(o) => Some(o.path_name.clone()),
+            _ => None,
+          })?;
+          let amount = properties.iter().find_map(|p| match &p.value {
+            PropertyValue::Int(i) => Some(*i),
+            _ => None,
+          })?;
+          Some((recipe, amount))
+        },
+        _ => None,
+      })
+      .collect()
+  }
+}
@@ -0,0 +1,282 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Serialize, Deserialize};
+
+use crate::property::*;
+use crate::save::*;
+
+const POWER_CONNECTION_PATH: &str = "PowerConnectionComponent";
+const POWER_CIRCUIT_PATH: &str = "/Script/FactoryGame.FGPowerCircuit";
+const GENERATOR_PREFIX: &str = "Build_Generator";
+const BATTERY_CLASS: &str = "Build_PowerStorageMk1_C";
+const SWITCH_CLASS: &str = "Build_PowerSwitch_C";
+const PRIORITY_SWITCH_CLASS: &str = "Build_PriorityPowerSwitch_C";
+
+/// Capacity of a single Power Storage in MWh, which isn't saved
+pub const BATTERY_CAPACITY: f32 = 100.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PowerNodeKind {
+  Generator,
+  Consumer,
+  Battery,
+}
+
+/// A building with a power info component, i.e. one which produces, stores
+/// or consumes power
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PowerNode {
+  pub instance_name: String,
+  pub kind: PowerNodeKind,
+  /// Maximum production in MW
+  pub production_capacity: f32,
+  /// Consumption the building is asking for in MW
+  pub consumption: f32,
+  /// Stored energy in MWh, batteries only
+  pub stored: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PowerSwitch {
+  pub instance_name: String,
+  pub is_on: bool,
+  /// Priority of a priority power switch, None for a regular switch
+  pub priority: Option<i32>,
+}
+
+/// A set of power connections joined together by wires and closed switches
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PowerCircuit {
+  /// The ID the game assigned to the circuit, if the save records one for
+  /// its connections
+  pub circuit_id: Option<i32>,
+  /// Instance names of the connection components in the circuit
+  pub connections: Vec<String>,
+  pub nodes: Vec<PowerNode>,
+  /// Switches with at least one side connected to this circuit
+  pub switches: Vec<String>,
+  pub production_capacity: f32,
+  pub consumption: f32,
+  pub battery_stored: f32,
+  pub battery_capacity: f32,
+  pub is_fuse_triggered: bool,
+}
+
+impl PowerCircuit {
+  pub fn generators(&self) -> impl Iterator<Item = &PowerNode> {
+    self.nodes.iter().filter(|n| n.kind == PowerNodeKind::Generator)
+  }
+
+  pub fn consumers(&self) -> impl Iterator<Item = &PowerNode> {
+    self.nodes.iter().filter(|n| n.kind == PowerNodeKind::Consumer)
+  }
+
+  pub fn batteries(&self) -> impl Iterator<Item = &PowerNode> {
+    self.nodes.iter().filter(|n| n.kind == PowerNodeKind::Battery)
+  }
+
+  /// Whether the circuit can't power its consumers, neither from
+  /// generators nor from batteries
+  pub fn is_unpowered(&self) -> bool {
+    self.production_capacity <= 0.0 && self.battery_stored <= 0.0
+  }
+}
+
+/// The power network of a save. Circuits are rebuilt from wires, switches
+/// and the connections of each building rather than taken from the circuit
+/// subsystem, so they reflect what's actually connected
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PowerGrid {
+  pub circuits: Vec<PowerCircuit>,
+  pub switches: Vec<PowerSwitch>,
+  /// Consumers in a circuit without any generator or charged battery,
+  /// including those not connected at all
+  pub isolated_consumers: Vec<String>,
+}
+
+/// Disjoint sets of connection indices
+struct UnionFind {
+  parents: Vec<usize>,
+}
+
+impl UnionFind {
+  fn new(len: usize) -> UnionFind {
+    UnionFind { parents: (0..len).collect() }
+  }
+
+  fn find(&mut self, i: usize) -> usize {
+    let mut root = i;
+    while self.parents[root] != root {
+      root = self.parents[root];
+    }
+    let mut i = i;
+    while self.parents[i] != root {
+      let next = self.parents[i];
+      self.parents[i] = root;
+      i = next;
+    }
+    root
+  }
+
+  fn union(&mut self, a: usize, b: usize) {
+    let (a, b) = (self.find(a), self.find(b));
+    if a != b {
+      self.parents[b] = a;
+    }
+  }
+}
+
+impl PowerGrid {
+  pub fn from_save(save: &Save) -> PowerGrid {
+    let mut grid = PowerGrid::default();
+    let save_index = save.index();
+
+    // Every power connection, indexed for the union-find
+    let connections: Vec<(&ComponentHeader, &Object)> = save.iter_objects()
+      .filter_map(|(object_header, object)| match object_header {
+        ObjectHeader::Component(c) if c.type_path.ends_with(POWER_CONNECTION_PATH) => Some((c, object)),
+        _ => None,
+      })
+      .collect();
+    let index: HashMap<&String, usize> = connections.iter()
+      .enumerate()
+      .map(|(i, (c, _))| (&c.instance_name, i))
+      .collect();
+    let mut sets = UnionFind::new(connections.len());
+
+    // Wires join the connections at both of their ends
+    for (_, object) in save.iter_objects() {
+      if let Some(ObjectExtra::PowerLine(line)) = object.get_extra() {
+        if let (Some(a), Some(b)) = (index.get(&line.source.path_name), index.get(&line.target.path_name)) {
+          sets.union(*a, *b);
+        }
+      }
+    }
+
+    // Hidden connections are made by the game itself, e.g. between a
+    // building and the pole it's snapped to
+    for (i, (_, object)) in connections.iter().enumerate() {
+      for hidden in property_references(object.get_properties(), "mHiddenConnections") {
+        if let Some(j) = index.get(hidden) {
+          sets.union(i, *j);
+        }
+      }
+    }
+
+    let mut connections_by_actor: HashMap<&String, Vec<usize>> = HashMap::new();
+    for (i, (c, _)) in connections.iter().enumerate() {
+      connections_by_actor.entry(&c.parent_actor_name).or_default().push(i);
+    }
+
+    // The connections of a building are all part of the same circuit,
+    // except for those of a switch which is off
+    let mut nodes: Vec<(usize, PowerNode)> = vec![];
+    let mut switch_connections: Vec<(usize, &String)> = vec![];
+    for (object_header, object) in save.iter_objects() {
+      let ObjectHeader::Actor(actor) = object_header else {
+        continue;
+      };
+      let Some(actor_connections) = connections_by_actor.get(&actor.instance_name) else {
+        continue;
+      };
+      let properties = object.get_properties();
+      let class = class_name(&actor.type_path);
+
+      let is_switch = class == SWITCH_CLASS || class == PRIORITY_SWITCH_CLASS;
+      if is_switch {
+        let is_on = property_bool(properties, "mIsSwitchOn").unwrap_or(false);
+        grid.switches.push(PowerSwitch {
+          instance_name: actor.instance_name.clone(),
+          is_on,
+          priority: if class == PRIORITY_SWITCH_CLASS { Some(property_int(properties, "mPriority").unwrap_or(0)) } else { None },
+        });
+        for i in actor_connections {
+          switch_connections.push((*i, &actor.instance_name));
+        }
+        if !is_on {
+          continue;
+        }
+      }
+
+      for pair in actor_connections.windows(2) {
+        sets.union(pair[0], pair[1]);
+      }
+
+      let power_info = match find_property(properties, "mPowerInfo").map(|p| &p.value) {
+        Some(PropertyValue::Object(o)) => save_index.find_object(&o.path_name).map(|(_, o)| o.get_properties()),
+        _ => None,
+      };
+      if let Some(power_info) = power_info {
+        let kind = if class.starts_with(GENERATOR_PREFIX) {
+          PowerNodeKind::Generator
+        } else if class == BATTERY_CLASS {
+          PowerNodeKind::Battery
+        } else {
+          PowerNodeKind::Consumer
+        };
+        nodes.push((actor_connections[0], PowerNode {
+          instance_name: actor.instance_name.clone(),
+          kind,
+          production_capacity: property_f32(power_info, "mBaseProduction").unwrap_or(0.0) + property_f32(power_info, "mDynamicProductionCapacity").unwrap_or(0.0),
+          consumption: property_f32(power_info, "mTargetConsumption").unwrap_or(0.0),
+          stored: if kind == PowerNodeKind::Battery { property_f32(properties, "mPowerStore").unwrap_or(0.0) } else { 0.0 },
+        }));
+      }
+    }
+
+    // Fuse state is kept on the game's own circuit objects, looked up by
+    // the circuit ID recorded on connections
+    let fuses: HashMap<i32, bool> = save.iter_objects()
+      .filter(|(object_header, _)| object_header.get_type_path() == POWER_CIRCUIT_PATH)
+      .map(|(_, object)| {
+        let properties = object.get_properties();
+        (property_int(properties, "mCircuitID").unwrap_or(0), property_bool(properties, "mIsFuseTriggered").unwrap_or(false))
+      })
+      .collect();
+
+    let mut circuits: BTreeMap<usize, PowerCircuit> = BTreeMap::new();
+    for (i, (c, object)) in connections.iter().enumerate() {
+      let circuit = circuits.entry(sets.find(i)).or_default();
+      circuit.connections.push(c.instance_name.clone());
+      if circuit.circuit_id.is_none() {
+        circuit.circuit_id = property_int(object.get_properties(), "mCircuitID");
+      }
+    }
+    for (i, node) in nodes {
+      let circuit = circuits.entry(sets.find(i)).or_default();
+      circuit.production_capacity += node.production_capacity;
+      if node.kind == PowerNodeKind::Battery {
+        circuit.battery_stored += node.stored;
+        circuit.battery_capacity += BATTERY_CAPACITY;
+      } else {
+        circuit.consumption += node.consumption;
+      }
+      circuit.nodes.push(node);
+    }
+    for (i, switch) in switch_connections {
+      let circuit = circuits.entry(sets.find(i)).or_default();
+      if !circuit.switches.contains(switch) {
+        circuit.switches.push(switch.clone());
+      }
+    }
+    for circuit in circuits.values_mut() {
+      circuit.is_fuse_triggered = circuit.circuit_id.and_then(|id| fuses.get(&id).copied()).unwrap_or(false);
+    }
+
+    grid.circuits = circuits.into_values().collect();
+    grid.isolated_consumers = grid.circuits.iter()
+      .filter(|c| c.is_unpowered())
+      .flat_map(|c| c.consumers().map(|n| n.instance_name.clone()))
+      .collect();
+
+    grid
+  }
+
+  /// Finds the circuit a building or one of its connections belongs to
+  pub fn circuit_of(&self, instance_name: &str) -> Option<&PowerCircuit> {
+    self.circuits.iter().find(|c| {
+      c.nodes.iter().any(|n| n.instance_name == instance_name) ||
+      c.connections.iter().any(|n| n == instance_name || n.strip_prefix(instance_name).is_some_and(|rest| rest.starts_with('.')))
+    })
+  }
+}
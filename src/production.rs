@@ -0,0 +1,134 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Serialize, Deserialize};
+
+use crate::buildings::Manufacturer;
+use crate::region::Region;
+use crate::save::*;
+use crate::Result;
+
+/// The game's machine recipes, default and alternate, as of version 1.0.
+/// Workbench and build gun recipes are left out
+const EMBEDDED_RECIPES: &str = include_str!("data/recipes.json");
+
+/// Amounts are per production cycle, in items for solids and in cubic
+/// metres for fluids
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Recipe {
+  /// Seconds per production cycle at 100% clock speed
+  pub duration: f32,
+  pub ingredients: Vec<(String, f32)>,
+  pub products: Vec<(String, f32)>,
+}
+
+/// Recipes keyed by class name, e.g. `Recipe_IronPlate_C`, with items also
+/// named by class name, e.g. `Desc_IronPlate_C`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RecipeDatabase {
+  pub recipes: HashMap<String, Recipe>,
+}
+
+impl RecipeDatabase {
+  pub fn embedded() -> RecipeDatabase {
+    RecipeDatabase::from_json(EMBEDDED_RECIPES).unwrap_or_default()
+  }
+
+  /// Reads recipes from a JSON object of the same shape as the embedded
+  /// database, mapping recipe class names to recipes
+  pub fn from_json(json: &str) -> Result<RecipeDatabase> {
+    Ok(RecipeDatabase {
+      recipes: serde_json::from_str(json)?,
+    })
+  }
+
+  /// Looks a recipe up by class name or full path
+  pub fn get(&self, recipe: &str) -> Option<&Recipe> {
+    self.recipes.get(class_name(recipe))
+  }
+}
+
+/// Items per minute of a single item
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ItemRate {
+  pub produced: f64,
+  pub consumed: f64,
+}
+
+impl ItemRate {
+  /// Positive for a surplus, negative for a deficit
+  pub fn net(&self) -> f64 {
+    self.produced - self.consumed
+  }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProductionReport {
+  pub items: BTreeMap<String, ItemRate>,
+  /// Recipes in use which the database doesn't know about, so their
+  /// machines aren't accounted for
+  pub unknown_recipes: BTreeSet<String>,
+}
+
+impl ProductionReport {
+  /// Items consumed faster than they're produced, with their deficit
+  pub fn deficits(&self) -> Vec<(&String, f64)> {
+    self.items.iter()
+      .map(|(item, rate)| (item, rate.net()))
+      .filter(|(_, net)| *net < 0.0)
+      .collect()
+  }
+
+  /// Items produced faster than they're consumed, with their surplus
+  pub fn surpluses(&self) -> Vec<(&String, f64)> {
+    self.items.iter()
+      .map(|(item, rate)| (item, rate.net()))
+      .filter(|(_, net)| *net > 0.0)
+      .collect()
+  }
+}
+
+/// Sums the theoretical rates of the given machines running without
+/// interruption. Clock speed scales both ingredients and products while
+/// somersloop amplification only scales products. Machines on standby or
+/// without a recipe are skipped
+pub fn production_report<'a, I: IntoIterator<Item = &'a Manufacturer>>(manufacturers: I, database: &RecipeDatabase) -> ProductionReport {
+  let mut report = ProductionReport::default();
+
+  for manufacturer in manufacturers {
+    if manufacturer.is_production_paused {
+      continue;
+    }
+    let Some(recipe_path) = &manufacturer.recipe else {
+      continue;
+    };
+    let Some(recipe) = database.get(recipe_path) else {
+      report.unknown_recipes.insert(class_name(recipe_path).to_string());
+      continue;
+    };
+    if recipe.duration <= 0.0 {
+      continue;
+    }
+
+    let cycles_per_minute = 60.0 / recipe.duration as f64;
+    for (item, amount) in &recipe.ingredients {
+      report.items.entry(item.clone()).or_default().consumed += *amount as f64 * cycles_per_minute * manufacturer.input_rate_multiplier() as f64;
+    }
+    for (item, amount) in &recipe.products {
+      report.items.entry(item.clone()).or_default().produced += *amount as f64 * cycles_per_minute * manufacturer.output_rate_multiplier() as f64;
+    }
+  }
+
+  report
+}
+
+impl Save {
+  /// Computes the production report of every machine in the save, or only
+  /// those inside a region
+  pub fn production_report(&self, region: Option<&Region>, database: &RecipeDatabase) -> ProductionReport {
+    let manufacturers = self.manufacturers();
+    production_report(
+      manufacturers.iter().filter(|m| region.is_none_or(|r| r.contains(&m.position))),
+      database,
+    )
+  }
+}
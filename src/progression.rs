@@ -0,0 +1,134 @@
+use serde::{Serialize, Deserialize};
+
+use crate::property::*;
+use crate::save::*;
+
+const SCHEMATIC_MANAGER_CLASS: &str = "BP_SchematicManager_C";
+const RESEARCH_MANAGER_CLASS: &str = "BP_ResearchManager_C";
+const GAME_PHASE_MANAGER_CLASS: &str = "BP_GamePhaseManager_C";
+const RECIPE_MANAGER_CLASS: &str = "BP_RecipeManager_C";
+
+const MILESTONE_PREFIX: &str = "Schematic_";
+const HARD_DRIVE_RESEARCH_PREFIX: &str = "Research_HardDrive";
+const ALTERNATE: &str = "Alternate";
+
+/// MAM research which was started but hasn't finished yet
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OngoingResearch {
+  pub schematic: String,
+  pub research_tree: Option<String>,
+  /// Seconds until the research completes, when recorded
+  pub time_remaining: Option<f64>,
+}
+
+/// The world's progression, read from the schematic, research, recipe and
+/// game phase subsystems. Schematics and recipes are referred to by path
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Progression {
+  pub purchased_schematics: Vec<String>,
+  pub available_schematics: Vec<String>,
+  /// The milestone selected in the HUB
+  pub active_milestone: Option<String>,
+  /// The highest tier of any purchased milestone
+  pub tier: u32,
+  pub unlocked_research_trees: Vec<String>,
+  pub ongoing_research: Vec<OngoingResearch>,
+  /// Number of hard drives analysed in the MAM
+  pub hard_drives_researched: usize,
+  /// Alternate recipe schematics picked as hard drive rewards
+  pub hard_drive_rewards: Vec<String>,
+  pub alternate_recipes: Vec<String>,
+  /// The current Space Elevator project phase
+  pub game_phase: Option<String>,
+  /// Parts delivered to the Space Elevator towards the next phase
+  pub delivered_parts: Vec<(String, i32)>,
+}
+
+fn struct_elements<'a>(properties: &'a [Property], name: &str) -> Vec<&'a Vec<Property>> {
+  match find_property(properties, name).map(|p| &p.value) {
+    Some(PropertyValue::Array(a)) => a.elements.iter()
+      .filter_map(|e| match e {
+        ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(properties)) => Some(properties),
+        _ => None,
+      })
+      .collect(),
+    _ => vec![],
+  }
+}
+
+/// Reads the tier out of milestone class names such as `Schematic_3-2_C`
+fn milestone_tier(schematic: &str) -> Option<u32> {
+  let name = class_name(schematic).strip_prefix(MILESTONE_PREFIX)?;
+  let (tier, _) = name.split_once('-')?;
+  tier.parse().ok()
+}
+
+impl Save {
+  fn find_subsystem(&self, class: &str) -> Option<&Vec<Property>> {
+    self.iter_objects()
+      .find(|(object_header, _)| object_header.get_class_name() == class)
+      .map(|(_, object)| object.get_properties())
+  }
+
+  pub fn progression(&self) -> Progression {
+    let mut progression = Progression::default();
+
+    if let Some(properties) = self.find_subsystem(SCHEMATIC_MANAGER_CLASS) {
+      progression.purchased_schematics = property_references(properties, "mPurchasedSchematics").into_iter().cloned().collect();
+      progression.available_schematics = property_references(properties, "mAvailableSchematics").into_iter().cloned().collect();
+      progression.active_milestone = property_reference(properties, "mActiveSchematic").cloned();
+    }
+    progression.tier = progression.purchased_schematics.iter()
+      .filter_map(|s| milestone_tier(s))
+      .max()
+      .unwrap_or(0);
+    progression.hard_drives_researched = progression.purchased_schematics.iter()
+      .filter(|s| class_name(s).starts_with(HARD_DRIVE_RESEARCH_PREFIX))
+      .count();
+    progression.hard_drive_rewards = progression.purchased_schematics.iter()
+      .filter(|s| class_name(s).contains(ALTERNATE))
+      .cloned()
+      .collect();
+
+    if let Some(properties) = self.find_subsystem(RESEARCH_MANAGER_CLASS) {
+      progression.unlocked_research_trees = property_references(properties, "mUnlockedResearchTrees").into_iter().cloned().collect();
+      progression.ongoing_research = struct_elements(properties, "mOngoingResearch").into_iter()
+        .filter_map(|research| Some(OngoingResearch {
+          schematic: property_reference(research, "Schematic").cloned()?,
+          research_tree: property_reference(research, "InitiatingResearchTree").cloned(),
+          time_remaining: research.iter().find_map(|p| match &p.value {
+            PropertyValue::Float(f) => Some(*f as f64),
+            PropertyValue::Double(d) => Some(*d),
+            _ => None,
+          }),
+        }))
+        .collect();
+    }
+
+    if let Some(properties) = self.find_subsystem(RECIPE_MANAGER_CLASS) {
+      progression.alternate_recipes = property_references(properties, "mAvailableRecipes").into_iter()
+        .filter(|r| class_name(r).contains(ALTERNATE))
+        .cloned()
+        .collect();
+    }
+
+    if let Some(properties) = self.find_subsystem(GAME_PHASE_MANAGER_CLASS) {
+      progression.game_phase = property_reference(properties, "mCurrentGamePhase").cloned();
+      progression.delivered_parts = struct_elements(properties, "mTargetGamePhasePaidOffCosts").into_iter()
+        .filter_map(|cost| {
+          let item = cost.iter().find_map(|p| match &p.value {
+            PropertyValue::Object(o) if !o.path_name.is_empty() => Some(o.path_name.clone()),
+            _ => None,
+          })?;
+          let amount = cost.iter().find_map(|p| match &p.value {
+            PropertyValue::Int(i) => Some(*i),
+            _ => None,
+          })?;
+          Some((item, amount))
+        })
+        .collect();
+    }
+
+    progression
+  }
+}
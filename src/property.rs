@@ -48,9 +48,145 @@ pub struct Property {
   pub size: i32,
   pub index: i32,
   pub guid: Option<String>,
+  /// The 17 bytes following a struct property's type, written back as they
+  /// were (or as zeroes when there are none)
+  #[serde(default)]
+  pub struct_guid: Option<Vec<u8>>,
   pub value: PropertyValue,
 }
 
+impl Property {
+  /// Creates a property whose type is derived from its value; its size is
+  /// computed when it's written
+  pub fn new(name: &str, value: PropertyValue) -> Property {
+    Property {
+      name: name.to_string(),
+      r#type: value.type_name().to_string(),
+      size: 0,
+      index: 0,
+      guid: None,
+      struct_guid: None,
+      value,
+    }
+  }
+}
+
+impl PropertyValue {
+  /// The property type as it appears in the save without the "Property"
+  /// suffix, e.g. "Int" for `IntProperty`
+  pub fn type_name(&self) -> &'static str {
+    match self {
+      PropertyValue::Bool(_) => "Bool",
+      PropertyValue::Int8(_) => "Int8",
+      PropertyValue::Int(_) => "Int",
+      PropertyValue::UInt32(_) => "UInt32",
+      PropertyValue::Int64(_) => "Int64",
+      PropertyValue::UInt64(_) => "UInt64",
+      PropertyValue::Float(_) => "Float",
+      PropertyValue::Double(_) => "Double",
+      PropertyValue::String(_) => "Str",
+      PropertyValue::Object(_) => "Object",
+      PropertyValue::Enum(_) => "Enum",
+      PropertyValue::Byte(_) => "Byte",
+      PropertyValue::Text(_) => "Text",
+      PropertyValue::Array(_) => "Array",
+      PropertyValue::Map(_) => "Map",
+      PropertyValue::Set(_) => "Set",
+      PropertyValue::Struct(_) => "Struct",
+    }
+  }
+}
+
+/// Finds the first property with the given name
+pub fn find_property<'a>(properties: &'a [Property], name: &str) -> Option<&'a Property> {
+  properties.iter().find(|p| p.name == name)
+}
+
+/// Finds the first property with the given name
+pub fn find_property_mut<'a>(properties: &'a mut [Property], name: &str) -> Option<&'a mut Property> {
+  properties.iter_mut().find(|p| p.name == name)
+}
+
+/// Replaces the property with the given name or adds it
+pub fn set_property_value(properties: &mut Vec<Property>, name: &str, value: PropertyValue) {
+  match find_property_mut(properties, name) {
+    Some(p) => p.value = value,
+    None => properties.push(Property::new(name, value)),
+  }
+}
+
+pub fn property_bool(properties: &[Property], name: &str) -> Option<bool> {
+  match find_property(properties, name).map(|p| &p.value) {
+    Some(PropertyValue::Bool(b)) => Some(*b != 0),
+    _ => None,
+  }
+}
+
+pub fn property_int(properties: &[Property], name: &str) -> Option<i32> {
+  match find_property(properties, name).map(|p| &p.value) {
+    Some(PropertyValue::Int(i)) => Some(*i),
+    _ => None,
+  }
+}
+
+/// Reads a float or a double property
+pub fn property_f32(properties: &[Property], name: &str) -> Option<f32> {
+  property_f64(properties, name).map(|f| f as f32)
+}
+
+/// Reads a float or a double property
+pub fn property_f64(properties: &[Property], name: &str) -> Option<f64> {
+  match find_property(properties, name).map(|p| &p.value) {
+    Some(PropertyValue::Float(f)) => Some(*f as f64),
+    Some(PropertyValue::Double(d)) => Some(*d),
+    _ => None,
+  }
+}
+
+/// Reads a string property, or the plain text of a text property
+pub fn property_text(properties: &[Property], name: &str) -> Option<String> {
+  match find_property(properties, name).map(|p| &p.value) {
+    Some(PropertyValue::String(s)) => Some(s.clone()),
+    Some(PropertyValue::Text(t)) => match &t.value {
+      TextPropertyHistory::BaseHistory(h) => Some(h.value.clone()),
+      TextPropertyHistory::NoneHistory(h) => Some(h.value.clone()),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+/// Reads an enum stored either as a byte property or an enum property
+pub fn property_enum<'a>(properties: &'a [Property], name: &str) -> Option<&'a String> {
+  match find_property(properties, name).map(|p| &p.value) {
+    Some(PropertyValue::Byte(b)) => b.string_value.as_ref(),
+    Some(PropertyValue::Enum(e)) => e.values().next(),
+    _ => None,
+  }
+}
+
+/// The path name an object property references, unless it's empty
+pub fn property_reference<'a>(properties: &'a [Property], name: &str) -> Option<&'a String> {
+  match find_property(properties, name).map(|p| &p.value) {
+    Some(PropertyValue::Object(o)) if !o.path_name.is_empty() => Some(&o.path_name),
+    _ => None,
+  }
+}
+
+/// The path names an array of object properties references, leaving out
+/// empty ones
+pub fn property_references<'a>(properties: &'a [Property], name: &str) -> Vec<&'a String> {
+  match find_property(properties, name).map(|p| &p.value) {
+    Some(PropertyValue::Array(a)) => a.elements.iter()
+      .filter_map(|e| match e {
+        ArrayPropertyValue::Object(o) if !o.path_name.is_empty() => Some(&o.path_name),
+        _ => None,
+      })
+      .collect(),
+    _ => vec![],
+  }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum MapPropertyKey {
@@ -79,6 +215,9 @@ pub enum MapPropertyValue {
   Struct(Vec<Property>),
 }
 
+/// For the maps of a few mods the reader stops at the first value, which is
+/// kept in the `unk_*`/`m_*` fields, leaving its key last in `keys` without
+/// a matching value
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct MapProperty {
   pub key_type: String,
@@ -145,6 +284,8 @@ pub struct InventoryStack {
   pub unk_int_1: i32,
   pub unk_int_2: i32,
   pub unk_struct_1: (String, StructPropertyValue),
+  #[serde(default)]
+  pub unk_struct_1_guid: Option<Vec<u8>>,
   pub unk_str_3: String,
 }
 
@@ -292,6 +433,9 @@ pub struct ArrayPropertyStruct {
 #[serde(untagged)]
 pub enum ArrayPropertyValue {
   Byte(u8),
+  /// Every element of a byte array, packed together as the reader keeps
+  /// them
+  Bytes(Vec<u8>),
   Bool(u8),
   Int(i32),
   Long(i64),
@@ -300,9 +444,18 @@ pub enum ArrayPropertyValue {
   Str(String),
   Text(TextProperty),
   Object(ObjectReference),
+  SoftObject(SoftObjectPath),
   Struct(ArrayPropertyStructValue),
 }
 
+/// A reference to an asset which isn't necessarily loaded, e.g. an icon
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SoftObjectPath {
+  pub package_name: String,
+  pub asset_name: String,
+  pub sub_path: String,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ArrayProperty {
   pub r#type: String,
@@ -310,6 +463,18 @@ pub struct ArrayProperty {
   pub elements: Vec<ArrayPropertyValue>,
 }
 
+impl ArrayProperty {
+  /// Number of elements as saved, counting each byte of packed bytes
+  pub fn num_elements(&self) -> usize {
+    self.elements.iter()
+      .map(|e| match e {
+        ArrayPropertyValue::Bytes(b) => b.len(),
+        _ => 1,
+      })
+      .sum()
+  }
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ByteProperty {
   pub r#type: String,
@@ -335,6 +500,7 @@ pub struct Argument {
 pub struct ArgumentHistory {
   pub source_format: Box<TextProperty>,
   pub num_arguments: i32,
+  pub arguments: Vec<Argument>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -0,0 +1,496 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Serialize, Deserialize};
+
+use crate::graph::Graph;
+use crate::property::*;
+use crate::save::*;
+use crate::spline::Spline;
+
+const TRACK_CONNECTION_PATH: &str = "/Script/FactoryGame.FGRailroadTrackConnectionComponent";
+const PLATFORM_CONNECTION_PATH: &str = "/Script/FactoryGame.FGTrainPlatformConnection";
+const STATION_IDENTIFIER_PATH: &str = "/Script/FactoryGame.FGTrainStationIdentifier";
+const TRAIN_PATH: &str = "/Script/FactoryGame.FGTrain";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignalKind {
+  Block,
+  Path,
+}
+
+impl SignalKind {
+  pub fn from_class_name(class_name: &str) -> Option<SignalKind> {
+    match class_name {
+      "Build_RailroadBlockSignal_C" => Some(SignalKind::Block),
+      "Build_RailroadPathSignal_C" => Some(SignalKind::Path),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlatformKind {
+  Station,
+  Freight,
+  Fluid,
+  Empty,
+}
+
+impl PlatformKind {
+  pub fn from_class_name(class_name: &str) -> Option<PlatformKind> {
+    if class_name == "Build_TrainStation_C" {
+      Some(PlatformKind::Station)
+    } else if class_name == "Build_TrainDockingStationLiquid_C" {
+      Some(PlatformKind::Fluid)
+    } else if class_name == "Build_TrainDockingStation_C" {
+      Some(PlatformKind::Freight)
+    } else if class_name.starts_with("Build_TrainPlatformEmpty") {
+      Some(PlatformKind::Empty)
+    } else {
+      None
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RailNode {
+  pub instance_name: String,
+  pub class_name: String,
+  pub position: Vector<f32>,
+  /// Length of the track along its curve, in world units
+  pub length: Option<f64>,
+  /// Index of the signal block the track belongs to
+  pub block: usize,
+  /// Connections which aren't linked to any other track
+  pub open_connections: Vec<String>,
+}
+
+/// A link from the end of one track into another. Links are stored from
+/// both sides, so a train leaving a track through `from_connection` enters
+/// the next one through `to_connection`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RailEdge {
+  pub from_connection: String,
+  pub to_connection: String,
+}
+
+/// A track connection leading to more than one track
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Switch {
+  pub connection: String,
+  pub track: String,
+  /// The switch control building, if one was saved
+  pub control: Option<String>,
+  pub position: Vector<f32>,
+  /// Index into `routes` of the track the switch is set to
+  pub switch_position: i32,
+  /// The connections the switch can lead to
+  pub routes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Signal {
+  pub instance_name: String,
+  pub kind: SignalKind,
+  pub position: Vector<f32>,
+  /// Connections a train passes the signal at
+  pub guarded_connections: Vec<String>,
+  /// Connections leading into the block the signal protects
+  pub observed_connections: Vec<String>,
+  /// Index of the block the signal protects
+  pub block: Option<usize>,
+}
+
+/// A station, freight or fluid platform, or an empty platform
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Platform {
+  pub instance_name: String,
+  pub kind: PlatformKind,
+  pub position: Vector<f32>,
+  /// Whether a freight or fluid platform loads trains rather than unloading
+  /// them
+  pub is_loading: Option<bool>,
+  /// The track built into the platform
+  pub track: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Station {
+  /// The station's identifier, which timetables refer to
+  pub instance_name: String,
+  pub name: Option<String>,
+  /// The station building followed by the platforms attached to it
+  pub platforms: Vec<Platform>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimetableStop {
+  /// Instance name of the station's identifier
+  pub station: String,
+  /// Seconds the train stays docked
+  pub duration: Option<f32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Train {
+  pub instance_name: String,
+  pub name: Option<String>,
+  pub is_self_driving: bool,
+  pub first_vehicle: Option<String>,
+  pub stops: Vec<TimetableStop>,
+  /// Index into `stops` of the stop the train is heading to
+  pub current_stop: Option<usize>,
+}
+
+/// The railway of a save: tracks as a graph, with the switches, signals,
+/// stations and trains built on them
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RailNetwork {
+  pub graph: Graph<RailNode, RailEdge>,
+  /// Track indices of each signal block
+  pub blocks: Vec<Vec<usize>>,
+  pub switches: Vec<Switch>,
+  pub signals: Vec<Signal>,
+  pub stations: Vec<Station>,
+  pub trains: Vec<Train>,
+}
+
+/// Reads a timetable's stops, each a struct naming a station and the rules
+/// for docking there
+fn timetable_stops(properties: &[Property]) -> Vec<TimetableStop> {
+  let Some(PropertyValue::Array(a)) = find_property(properties, "mStops").map(|p| &p.value) else {
+    return vec![];
+  };
+
+  a.elements.iter()
+    .filter_map(|e| match e {
+      ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(stop)) => Some(TimetableStop {
+        station: property_reference(stop, "Station")?.clone(),
+        duration: match find_property(stop, "DockingRuleSet").map(|p| &p.value) {
+          Some(PropertyValue::Struct((_, StructPropertyValue::Properties(rules)))) => property_f32(rules, "DockForDuration"),
+          _ => None,
+        },
+      }),
+      _ => None,
+    })
+    .collect()
+}
+
+impl RailNetwork {
+  pub fn from_save(save: &Save) -> RailNetwork {
+    let mut graph: Graph<RailNode, RailEdge> = Graph::default();
+    let index = save.index();
+
+    let connections: Vec<(&ComponentHeader, &Object)> = save.iter_objects()
+      .filter_map(|(object_header, object)| match object_header {
+        ObjectHeader::Component(c) if c.type_path == TRACK_CONNECTION_PATH => Some((c, object)),
+        _ => None,
+      })
+      .collect();
+    let parents: HashMap<&String, &String> = connections.iter()
+      .map(|(c, _)| (&c.instance_name, &c.parent_actor_name))
+      .collect();
+
+    for (c, _) in &connections {
+      if graph.index_of(&c.parent_actor_name).is_some() {
+        continue;
+      }
+      let Some((ObjectHeader::Actor(actor), object)) = index.find_object(&c.parent_actor_name) else {
+        continue;
+      };
+      graph.add_node(&actor.instance_name, RailNode {
+        instance_name: actor.instance_name.clone(),
+        class_name: class_name(&actor.type_path).to_string(),
+        position: actor.position.clone(),
+        length: Spline::from_properties(object.get_properties()).map(|s| s.length()),
+        block: 0,
+        open_connections: vec![],
+      });
+    }
+
+    let mut switches = vec![];
+    for (c, object) in &connections {
+      let Some(i) = graph.index_of(&c.parent_actor_name) else {
+        continue;
+      };
+      let properties = object.get_properties();
+      let others: Vec<(&String, usize)> = property_references(properties, "mConnectedComponents").into_iter()
+        .filter_map(|other| Some((other, graph.index_of(parents.get(other)?)?)))
+        .collect();
+      if others.is_empty() {
+        graph.nodes[i].open_connections.push(c.instance_name.clone());
+        continue;
+      }
+
+      if others.len() > 1 {
+        let control = property_reference(properties, "mSwitchControl");
+        let position = control
+          .and_then(|name| match index.find_object(name) {
+            Some((ObjectHeader::Actor(actor), _)) => Some(actor.position.clone()),
+            _ => None,
+          })
+          .unwrap_or_else(|| graph.nodes[i].position.clone());
+        switches.push(Switch {
+          connection: c.instance_name.clone(),
+          track: c.parent_actor_name.clone(),
+          control: control.cloned(),
+          position,
+          switch_position: property_int(properties, "mSwitchPosition").unwrap_or(0),
+          routes: others.iter().map(|(other, _)| (*other).clone()).collect(),
+        });
+      }
+
+      for (other, j) in others {
+        graph.add_edge(i, j, RailEdge {
+          from_connection: c.instance_name.clone(),
+          to_connection: other.clone(),
+        });
+      }
+    }
+
+    let mut signals = vec![];
+    for (object_header, object) in save.iter_objects() {
+      let ObjectHeader::Actor(actor) = object_header else {
+        continue;
+      };
+      let Some(kind) = SignalKind::from_class_name(class_name(&actor.type_path)) else {
+        continue;
+      };
+      let properties = object.get_properties();
+      signals.push(Signal {
+        instance_name: actor.instance_name.clone(),
+        kind,
+        position: actor.position.clone(),
+        guarded_connections: property_references(properties, "mGuardedConnections").into_iter().cloned().collect(),
+        observed_connections: property_references(properties, "mObservedConnections").into_iter().cloned().collect(),
+        block: None,
+      });
+    }
+
+    // Signals split the track into blocks, so a block is a group of tracks
+    // connected without passing a signalled connection. Links are stored
+    // from both sides, so following those leaving a track finds them all
+    let signalled: HashSet<&String> = signals.iter()
+      .flat_map(|s| s.guarded_connections.iter().chain(&s.observed_connections))
+      .collect();
+    let mut blocks: Vec<Vec<usize>> = vec![];
+    let mut seen = vec![false; graph.nodes.len()];
+    for start in 0..graph.nodes.len() {
+      if seen[start] {
+        continue;
+      }
+      seen[start] = true;
+      let mut block = vec![];
+      let mut queue = VecDeque::from([start]);
+      while let Some(i) = queue.pop_front() {
+        block.push(i);
+        for e in graph.outgoing(i) {
+          if signalled.contains(&e.data.from_connection) || signalled.contains(&e.data.to_connection) {
+            continue;
+          }
+          if !seen[e.to] {
+            seen[e.to] = true;
+            queue.push_back(e.to);
+          }
+        }
+      }
+      block.sort();
+      for i in &block {
+        graph.nodes[*i].block = blocks.len();
+      }
+      blocks.push(block);
+    }
+    for signal in &mut signals {
+      signal.block = signal.observed_connections.iter()
+        .find_map(|c| graph.index_of(parents.get(c)?))
+        .map(|i| graph.nodes[i].block);
+    }
+
+    RailNetwork {
+      graph,
+      blocks,
+      switches,
+      signals,
+      stations: stations(&index),
+      trains: trains(&index),
+    }
+  }
+
+  pub fn node(&self, instance_name: &str) -> Option<&RailNode> {
+    self.graph.node(instance_name)
+  }
+
+  /// Finds a station by its identifier's instance name or by its name
+  pub fn station(&self, station: &str) -> Option<&Station> {
+    self.stations.iter().find(|s| s.instance_name == station || s.name.as_deref() == Some(station))
+  }
+
+  /// Finds a train by its instance name or by its name
+  pub fn train(&self, train: &str) -> Option<&Train> {
+    self.trains.iter().find(|t| t.instance_name == train || t.name.as_deref() == Some(train))
+  }
+
+  /// The stations of a train's timetable in order. Stops at stations which
+  /// no longer exist are left out
+  pub fn stops(&self, train: &Train) -> Vec<&Station> {
+    train.stops.iter().filter_map(|s| self.station(&s.station)).collect()
+  }
+
+  /// Indices of the tracks a train can drive to from a track, leaving it
+  /// in either direction. Trains can't turn around on a track, so each
+  /// track is left through the end opposite the one it was entered by
+  pub fn reachable_tracks(&self, start: usize) -> Vec<usize> {
+    let leaving = |track: usize| self.graph.outgoing_edges(track).iter().copied();
+
+    let mut seen_edges = vec![false; self.graph.edges.len()];
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    for k in leaving(start) {
+      seen_edges[k] = true;
+      queue.push_back(k);
+    }
+
+    let mut tracks = vec![];
+    let mut seen_tracks: HashSet<usize> = HashSet::from([start]);
+    while let Some(k) = queue.pop_front() {
+      let edge = &self.graph.edges[k];
+      if seen_tracks.insert(edge.to) {
+        tracks.push(edge.to);
+      }
+      for next in leaving(edge.to) {
+        if self.graph.edges[next].data.from_connection != edge.data.to_connection && !seen_edges[next] {
+          seen_edges[next] = true;
+          queue.push_back(next);
+        }
+      }
+    }
+    tracks
+  }
+
+  /// Whether a train at one station can drive to another
+  pub fn is_reachable(&self, from: &str, to: &str) -> bool {
+    let (Some(from), Some(to)) = (self.station(from), self.station(to)) else {
+      return false;
+    };
+    let tracks = |station: &Station| -> Vec<usize> {
+      station.platforms.iter()
+        .filter_map(|p| self.graph.index_of(p.track.as_ref()?))
+        .collect()
+    };
+
+    let targets = tracks(to);
+    tracks(from).into_iter().any(|start| {
+      targets.contains(&start) || self.reachable_tracks(start).iter().any(|i| targets.contains(i))
+    })
+  }
+
+  /// Pairs of consecutive stops in a train's timetable where the next
+  /// station can't be reached from the previous one
+  pub fn unreachable_stops<'a>(&'a self, train: &Train) -> Vec<(&'a Station, &'a Station)> {
+    let stops = self.stops(train);
+    (0..stops.len())
+      .map(|i| (stops[i], stops[(i + 1) % stops.len()]))
+      .filter(|(from, to)| !self.is_reachable(&from.instance_name, &to.instance_name))
+      .collect()
+  }
+
+  /// Switches in blocks no signal protects, where trains coming from
+  /// different directions can meet and lock each other up
+  pub fn unsignalled_junctions(&self) -> Vec<&Switch> {
+    let protected: HashSet<usize> = self.signals.iter().filter_map(|s| s.block).collect();
+    self.switches.iter()
+      .filter(|s| self.node(&s.track).is_some_and(|n| !protected.contains(&n.block)))
+      .collect()
+  }
+}
+
+/// Reads the stations of a save, following each station building's
+/// platform connections to the platforms docked to it
+fn stations(index: &SaveIndex) -> Vec<Station> {
+  let save = index.save;
+  let platform_links: HashMap<&String, Vec<&String>> = {
+    let parents: HashMap<&String, &String> = save.iter_objects()
+      .filter_map(|(object_header, _)| match object_header {
+        ObjectHeader::Component(c) if c.type_path == PLATFORM_CONNECTION_PATH => Some((&c.instance_name, &c.parent_actor_name)),
+        _ => None,
+      })
+      .collect();
+    let mut links: HashMap<&String, Vec<&String>> = HashMap::new();
+    for (object_header, object) in save.iter_objects() {
+      let ObjectHeader::Component(c) = object_header else {
+        continue;
+      };
+      if c.type_path != PLATFORM_CONNECTION_PATH {
+        continue;
+      }
+      if let Some(other) = property_reference(object.get_properties(), "mConnectedTo").and_then(|other| parents.get(other)) {
+        links.entry(&c.parent_actor_name).or_default().push(other);
+      }
+    }
+    links
+  };
+
+  let platform = |instance_name: &String| -> Option<Platform> {
+    let (ObjectHeader::Actor(actor), object) = index.find_object(instance_name)? else {
+      return None;
+    };
+    let kind = PlatformKind::from_class_name(class_name(&actor.type_path))?;
+    let properties = object.get_properties();
+    Some(Platform {
+      instance_name: actor.instance_name.clone(),
+      kind,
+      position: actor.position.clone(),
+      is_loading: match kind {
+        PlatformKind::Freight | PlatformKind::Fluid => property_bool(properties, "mIsInLoadMode"),
+        _ => None,
+      },
+      track: property_reference(properties, "mRailroadTrack").cloned(),
+    })
+  };
+
+  save.iter_objects()
+    .filter(|(object_header, _)| object_header.get_type_path() == STATION_IDENTIFIER_PATH)
+    .map(|(object_header, object)| {
+      let properties = object.get_properties();
+      let mut platforms = vec![];
+      if let Some(building) = property_reference(properties, "mStation") {
+        let mut seen: HashSet<&String> = HashSet::new();
+        let mut queue = VecDeque::from([building]);
+        while let Some(name) = queue.pop_front() {
+          if !seen.insert(name) {
+            continue;
+          }
+          platforms.extend(platform(name));
+          queue.extend(platform_links.get(name).into_iter().flatten());
+        }
+      }
+
+      Station {
+        instance_name: object_header.get_instance_name().clone(),
+        name: property_text(properties, "mStationName"),
+        platforms,
+      }
+    })
+    .collect()
+}
+
+fn trains(index: &SaveIndex) -> Vec<Train> {
+  index.save.iter_objects()
+    .filter(|(object_header, _)| object_header.get_type_path() == TRAIN_PATH)
+    .map(|(object_header, object)| {
+      let properties = object.get_properties();
+      let timetable = property_reference(properties, "mTimeTable")
+        .and_then(|name| index.find_object(name))
+        .map(|(_, timetable)| timetable.get_properties().as_slice());
+      Train {
+        instance_name: object_header.get_instance_name().clone(),
+        name: property_text(properties, "TrainName"),
+        is_self_driving: property_bool(properties, "mIsSelfDrivingEnabled").unwrap_or(false),
+        first_vehicle: property_reference(properties, "mFirstVehicle").cloned(),
+        stops: timetable.map(timetable_stops).unwrap_or_default(),
+        current_stop: timetable
+          .and_then(|t| property_int(t, "mCurrentStop"))
+          .and_then(|i| usize::try_from(i).ok()),
+      }
+    })
+    .collect()
+}
@@ -0,0 +1,228 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Serialize, Deserialize};
+
+use crate::edit::{NullReferences, RemapReferences};
+use crate::errors::ParseError;
+use crate::lightweight;
+use crate::save::*;
+use crate::visit::*;
+use crate::Result;
+
+/// Only actors under this path are considered part of a region; subsystems,
+/// players and objects placed with the map (e.g. resource nodes) are left
+/// alone
+const BUILDABLE_PATH: &str = "/Buildable/";
+
+/// An area of the world used to select actors by their position
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Region {
+  /// An axis-aligned box between two corners
+  Box {
+    min: Vector<f32>,
+    max: Vector<f32>,
+  },
+  /// A polygon on the X/Y plane, extruded between two heights
+  Polygon {
+    points: Vec<Vector2D<f32>>,
+    min_z: f32,
+    max_z: f32,
+  },
+}
+
+impl Region {
+  pub fn contains(&self, point: &Vector<f32>) -> bool {
+    match self {
+      Region::Box { min, max } => {
+        point.x >= min.x && point.x <= max.x &&
+        point.y >= min.y && point.y <= max.y &&
+        point.z >= min.z && point.z <= max.z
+      },
+      Region::Polygon { points, min_z, max_z } => {
+        if point.z < *min_z || point.z > *max_z {
+          return false;
+        }
+
+        // Even-odd rule: count the edges a ray towards +X crosses
+        let mut inside = false;
+        let mut j = points.len().wrapping_sub(1);
+        for (i, a) in points.iter().enumerate() {
+          let b = &points[j];
+          if (a.y > point.y) != (b.y > point.y) &&
+             point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+          }
+          j = i;
+        }
+        inside
+      },
+    }
+  }
+}
+
+impl Save {
+  /// Returns the instance names of the buildable actors positioned inside
+  /// the region. Lightweight buildables have no instance name, so they're
+  /// found with `LightweightBuildables::in_region` instead
+  pub fn select_actors(&self, region: &Region) -> Vec<String> {
+    self.iter_objects()
+      .filter_map(|(object_header, _)| match object_header {
+        ObjectHeader::Actor(a) => Some(a),
+        ObjectHeader::Component(_) => None,
+      })
+      .filter(|a| a.was_placed_in_level == 0 && a.type_path.contains(BUILDABLE_PATH))
+      .filter(|a| region.contains(&a.position))
+      .map(|a| a.instance_name.clone())
+      .collect()
+  }
+}
+
+/// Copies every buildable actor inside `region` of `source`, along with its
+/// components (inventories, connections, etc), into the persistent level of
+/// `target` moved by `offset`.
+///
+/// Copies get fresh instance names in `target` and references between them
+/// (e.g. belts connected to a machine inside the region) are remapped.
+/// References to any other object of `source` are cut: single references are
+/// cleared and lists of references drop them, which leaves connections at
+/// the region's border unconnected. Power lines are only copied when both of
+/// their ends are.
+///
+/// Lightweight buildables (foundations, walls etc.) inside the region are
+/// added to the lightweight buildable subsystem of `target`, also moved by
+/// `offset`. Fails without changing `target` if there are any to copy but
+/// `target` has no subsystem or its instances weren't decoded.
+///
+/// Returns the instance names in `source` mapped to those in `target`.
+/// Lightweight buildables have no instance names, so they aren't included
+pub fn copy_region(source: &Save, region: &Region, target: &mut Save, offset: &Vector<f32>) -> Result<HashMap<String, String>> {
+  if target.levels.is_empty() {
+    return Err(ParseError::MissingLevel);
+  }
+
+  let lightweight: Vec<(String, LightweightInstance)> = source.lightweight_buildables()
+    .map(|l| l.in_region(region)
+      .map(|(class, instance)| {
+        let mut instance = instance.clone();
+        let translation = &mut instance.transform.translation;
+        translation.x += offset.x as f64;
+        translation.y += offset.y as f64;
+        translation.z += offset.z as f64;
+        (class.to_string(), instance)
+      })
+      .collect())
+    .unwrap_or_default();
+  if !lightweight.is_empty() {
+    match target.lightweight_buildables() {
+      Some(l) if l.is_decoded() => {},
+      Some(_) => return Err(ParseError::UndecodedLightweightBuildables),
+      None => return Err(ParseError::MissingObject(lightweight::SUBSYSTEM_CLASS.to_string())),
+    }
+  }
+
+  let actors = source.select_actors(region);
+
+  let mut selected: HashSet<String> = HashSet::new();
+  for actor in &actors {
+    selected.insert(actor.clone());
+    selected.extend(source.component_names(actor)?);
+  }
+
+  // Wires whose other end is outside the region would dangle
+  let mut excluded: HashSet<String> = HashSet::new();
+  for actor in &actors {
+    if let Some((_, object)) = source.find_object(actor) {
+      if let Some(ObjectExtra::PowerLine(line)) = object.get_extra() {
+        if !selected.contains(&line.source.path_name) || !selected.contains(&line.target.path_name) {
+          excluded.insert(actor.clone());
+          excluded.extend(source.component_names(actor)?);
+        }
+      }
+    }
+  }
+  selected.retain(|name| !excluded.contains(name));
+
+  let mut remap = RemapReferences::default();
+  let mut reserved: HashSet<String> = HashSet::new();
+  for actor in actors.iter().filter(|a| selected.contains(*a)) {
+    let new_name = target.unique_instance_name_excluding(actor, &reserved);
+    reserved.insert(new_name.clone());
+    remap.names.insert(actor.clone(), new_name.clone());
+
+    for component in source.component_names(actor)? {
+      let new_component = match component.strip_prefix(actor.as_str()) {
+        Some(rest) => format!("{new_name}{rest}"),
+        None => target.unique_instance_name_excluding(&component, &reserved),
+      };
+      reserved.insert(new_component.clone());
+      remap.names.insert(component, new_component);
+    }
+  }
+
+  // Only references to other buildables are cut; references to subsystems
+  // and the like are kept since every save has them under the same names
+  let buildables: HashSet<&String> = source.iter_objects()
+    .filter_map(|(object_header, _)| match object_header {
+      ObjectHeader::Actor(a) if a.type_path.contains(BUILDABLE_PATH) => Some(&a.instance_name),
+      _ => None,
+    })
+    .collect();
+  let mut cut = NullReferences {
+    names: source.iter_objects()
+      .filter(|(object_header, _)| match object_header {
+        ObjectHeader::Actor(a) => buildables.contains(&a.instance_name),
+        ObjectHeader::Component(c) => buildables.contains(&c.parent_actor_name),
+      })
+      .map(|(object_header, _)| object_header.get_instance_name())
+      .filter(|name| !selected.contains(*name))
+      .cloned()
+      .collect(),
+  };
+
+  let level = target.levels.last_mut().ok_or(ParseError::MissingLevel)?;
+  for (object_header, object) in source.iter_objects() {
+    let name = object_header.get_instance_name();
+    let Some(new_name) = remap.names.get(name).cloned() else {
+      continue;
+    };
+
+    let mut object_header = object_header.clone();
+    let mut object = object.clone();
+
+    let mut path = VisitPath::new();
+    path.push(PathSegment::Object(name.clone()));
+    cut.visit_object_mut(&mut object, &mut path);
+    remap.visit_object_mut(&mut object, &mut path);
+
+    match &mut object_header {
+      ObjectHeader::Actor(a) => {
+        a.root_object = None;
+        a.position.x += offset.x;
+        a.position.y += offset.y;
+        a.position.z += offset.z;
+      },
+      ObjectHeader::Component(c) => {
+        c.root_object = None;
+        if let Some(parent) = remap.names.get(&c.parent_actor_name) {
+          c.parent_actor_name = parent.clone();
+        }
+      },
+    }
+    if let Object::Actor(a) = &mut object {
+      a.parent_object_root = String::new();
+      a.parent_object_root_is_map = !a.parent_object_name.is_empty();
+    }
+    object_header.set_instance_name(new_name);
+
+    level.object_headers.push(object_header);
+    level.objects.push(object);
+  }
+
+  if let Some(target_lightweight) = target.lightweight_buildables_mut() {
+    for (class, instance) in lightweight {
+      target_lightweight.add(&class, instance);
+    }
+  }
+
+  Ok(remap.names)
+}
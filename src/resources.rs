@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::property::*;
+use crate::save::*;
+use crate::Result;
+
+/// Nodes, wells and geysers of the default map with their resource, purity
+/// and position, as a JSON array of `NodeInfo`
+const EMBEDDED_NODES: &str = include_str!("data/resource_nodes.json");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResourceNodeKind {
+  Node,
+  Geyser,
+  /// A satellite node of a resource well
+  Well,
+  /// The center of a resource well, where the pressurizer goes
+  WellCore,
+}
+
+impl ResourceNodeKind {
+  /// Classifies a node by its instance name or type path, e.g.
+  /// `Persistent_Level:PersistentLevel.BP_ResourceNodeGeyser12`
+  pub fn from_name(name: &str) -> Option<ResourceNodeKind> {
+    let name = name.rsplit('.').next().unwrap_or_default();
+    if name.starts_with("BP_ResourceNodeGeyser") {
+      Some(ResourceNodeKind::Geyser)
+    } else if name.starts_with("BP_ResourceNode") {
+      Some(ResourceNodeKind::Node)
+    } else if name.starts_with("BP_FrackingSatellite") {
+      Some(ResourceNodeKind::Well)
+    } else if name.starts_with("BP_FrackingCore") {
+      Some(ResourceNodeKind::WellCore)
+    } else {
+      None
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Purity {
+  Impure,
+  Normal,
+  Pure,
+}
+
+impl Purity {
+  /// Parses the game's `EResourcePurity` values, e.g.
+  /// `EResourcePurity::RP_Pure`. Impure nodes are spelled `RP_Inpure`
+  pub fn from_enum_value(value: &str) -> Option<Purity> {
+    match value.rsplit("::").next().unwrap_or_default() {
+      "RP_Inpure" | "RP_Impure" => Some(Purity::Impure),
+      "RP_Normal" => Some(Purity::Normal),
+      "RP_Pure" => Some(Purity::Pure),
+      _ => None,
+    }
+  }
+
+  /// Extraction rate relative to a normal node
+  pub fn multiplier(&self) -> f32 {
+    match self {
+      Purity::Impure => 0.5,
+      Purity::Normal => 1.0,
+      Purity::Pure => 2.0,
+    }
+  }
+}
+
+/// A node as listed in a catalogue
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NodeInfo {
+  pub path_name: String,
+  pub kind: ResourceNodeKind,
+  /// Descriptor path of the resource, e.g. `.../Desc_OreIron.Desc_OreIron_C`
+  pub resource: String,
+  pub purity: Purity,
+  pub position: Vector<f32>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NodeCatalogue {
+  pub nodes: Vec<NodeInfo>,
+}
+
+impl NodeCatalogue {
+  pub fn embedded() -> NodeCatalogue {
+    NodeCatalogue::from_json(EMBEDDED_NODES).unwrap_or_default()
+  }
+
+  /// Reads a JSON array of nodes
+  pub fn from_json(json: &str) -> Result<NodeCatalogue> {
+    Ok(NodeCatalogue {
+      nodes: serde_json::from_str(json)?,
+    })
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExtractorKind {
+  Miner,
+  WaterExtractor,
+  OilPump,
+  ResourceWellExtractor,
+  ResourceWellPressurizer,
+  GeothermalGenerator,
+}
+
+impl ExtractorKind {
+  pub fn from_class_name(class_name: &str) -> Option<ExtractorKind> {
+    if class_name.starts_with("Build_MinerMk") {
+      Some(ExtractorKind::Miner)
+    } else {
+      match class_name {
+        "Build_WaterPump_C" => Some(ExtractorKind::WaterExtractor),
+        "Build_OilPump_C" => Some(ExtractorKind::OilPump),
+        "Build_FrackingExtractor_C" => Some(ExtractorKind::ResourceWellExtractor),
+        "Build_FrackingSmasher_C" => Some(ExtractorKind::ResourceWellPressurizer),
+        "Build_GeneratorGeoThermal_C" => Some(ExtractorKind::GeothermalGenerator),
+        _ => None,
+      }
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Extractor {
+  pub instance_name: String,
+  pub kind: ExtractorKind,
+  /// Mark of a miner, from 1 to 3
+  pub tier: Option<u8>,
+  /// Clock speed where 1.0 is 100%
+  pub clock: f32,
+  pub position: Vector<f32>,
+  /// Instance name of the node the extractor sits on, if any
+  pub node: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResourceNode {
+  pub instance_name: String,
+  pub kind: ResourceNodeKind,
+  /// Resource and purity are only known for catalogued nodes
+  pub resource: Option<String>,
+  pub purity: Option<Purity>,
+  pub position: Option<Vector<f32>>,
+  pub extractor: Option<Extractor>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Resources {
+  pub nodes: Vec<ResourceNode>,
+  /// Extractors which don't sit on a node, i.e. water extractors
+  pub unplaced_extractors: Vec<Extractor>,
+}
+
+impl Resources {
+  pub fn node(&self, instance_name: &str) -> Option<&ResourceNode> {
+    self.nodes.iter().find(|n| n.instance_name == instance_name)
+  }
+
+  /// Nodes without an extractor
+  pub fn unexploited(&self) -> impl Iterator<Item = &ResourceNode> {
+    self.nodes.iter().filter(|n| n.extractor.is_none())
+  }
+
+  /// Nodes without an extractor and with a known position within `radius`
+  /// of a point, closest first, with their distance
+  pub fn unexploited_near(&self, point: &Vector<f32>, radius: f32) -> Vec<(&ResourceNode, f32)> {
+    let mut nodes: Vec<(&ResourceNode, f32)> = self.unexploited()
+      .filter_map(|n| {
+        let p = n.position.as_ref()?;
+        let (dx, dy, dz) = (p.x - point.x, p.y - point.y, p.z - point.z);
+        Some((n, (dx * dx + dy * dy + dz * dz).sqrt()))
+      })
+      .filter(|(_, distance)| *distance <= radius)
+      .collect();
+    nodes.sort_by(|a, b| a.1.total_cmp(&b.1));
+    nodes
+  }
+}
+
+/// Reads the mark from class names such as `Build_MinerMk2_C`
+fn miner_tier(class_name: &str) -> Option<u8> {
+  class_name.strip_prefix("Build_MinerMk")?.trim_end_matches("_C").parse().ok()
+}
+
+impl Save {
+  /// Lists every node in the catalogue or mentioned by the save, joined to
+  /// the extractor occupying it
+  pub fn resources(&self, catalogue: &NodeCatalogue) -> Resources {
+    let mut resources = Resources::default();
+    let mut nodes: BTreeMap<String, ResourceNode> = BTreeMap::new();
+
+    for info in &catalogue.nodes {
+      nodes.insert(info.path_name.clone(), ResourceNode {
+        instance_name: info.path_name.clone(),
+        kind: info.kind,
+        resource: Some(info.resource.clone()),
+        purity: Some(info.purity),
+        position: Some(info.position.clone()),
+        extractor: None,
+      });
+    }
+
+    for (object_header, object) in self.iter_objects() {
+      let ObjectHeader::Actor(actor) = object_header else {
+        continue;
+      };
+      let class = class_name(&actor.type_path);
+
+      if let Some(kind) = ResourceNodeKind::from_name(class) {
+        let node = nodes.entry(actor.instance_name.clone()).or_insert_with(|| ResourceNode {
+          instance_name: actor.instance_name.clone(),
+          kind,
+          resource: None,
+          purity: None,
+          position: None,
+          extractor: None,
+        });
+        node.position = Some(actor.position.clone());
+
+        let properties = object.get_properties();
+        if node.purity.is_none() {
+          node.purity = property_enum(properties, "mPurity").and_then(|v| Purity::from_enum_value(v));
+        }
+        if node.resource.is_none() {
+          if let Some(PropertyValue::Object(o)) = find_property(properties, "mResourceClass").map(|p| &p.value) {
+            node.resource = (!o.path_name.is_empty()).then(|| o.path_name.clone());
+          }
+        }
+        continue;
+      }
+
+      let Some(kind) = ExtractorKind::from_class_name(class) else {
+        continue;
+      };
+      let properties = object.get_properties();
+      let extractor = Extractor {
+        instance_name: actor.instance_name.clone(),
+        kind,
+        tier: miner_tier(class),
+        clock: property_f32(properties, "mCurrentPotential").unwrap_or(1.0),
+        position: actor.position.clone(),
+        node: property_reference(properties, "mExtractableResource").cloned(),
+      };
+
+      match &extractor.node {
+        Some(node_name) => {
+          let node = nodes.entry(node_name.clone()).or_insert_with(|| ResourceNode {
+            instance_name: node_name.clone(),
+            kind: ResourceNodeKind::from_name(node_name).unwrap_or(ResourceNodeKind::Node),
+            resource: None,
+            purity: None,
+            position: None,
+            extractor: None,
+          });
+          // Extractors sit on their node, which is as close as an
+          // uncatalogued node gets to a position
+          node.position.get_or_insert_with(|| extractor.position.clone());
+          node.extractor = Some(extractor);
+        },
+        None => resources.unplaced_extractors.push(extractor),
+      }
+    }
+
+    resources.nodes = nodes.into_values().collect();
+    resources
+  }
+}
@@ -1,7 +1,11 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
+use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 
+use crate::errors::ParseError;
 use crate::property::*;
 
 const GAME_PATHS: [&str; 2] = [
@@ -11,17 +15,19 @@ const GAME_PATHS: [&str; 2] = [
 const PLAYER_STATE_PATHS: [&str; 1] = [
   "/Game/FactoryGame/Character/Player/BP_PlayerState.BP_PlayerState_C",
 ];
-const CONVEYOR_PATHS: [&str; 10] = [
+const CONVEYOR_PATHS: [&str; 12] = [
   "/Game/FactoryGame/Buildable/Factory/ConveyorBeltMk1/Build_ConveyorBeltMk1.Build_ConveyorBeltMk1_C",
   "/Game/FactoryGame/Buildable/Factory/ConveyorBeltMk2/Build_ConveyorBeltMk2.Build_ConveyorBeltMk2_C",
   "/Game/FactoryGame/Buildable/Factory/ConveyorBeltMk3/Build_ConveyorBeltMk3.Build_ConveyorBeltMk3_C",
   "/Game/FactoryGame/Buildable/Factory/ConveyorBeltMk4/Build_ConveyorBeltMk4.Build_ConveyorBeltMk4_C",
   "/Game/FactoryGame/Buildable/Factory/ConveyorBeltMk5/Build_ConveyorBeltMk5.Build_ConveyorBeltMk5_C",
+  "/Game/FactoryGame/Buildable/Factory/ConveyorBeltMk6/Build_ConveyorBeltMk6.Build_ConveyorBeltMk6_C",
   "/Game/FactoryGame/Buildable/Factory/ConveyorLiftMk1/Build_ConveyorLiftMk1.Build_ConveyorLiftMk1_C",
   "/Game/FactoryGame/Buildable/Factory/ConveyorLiftMk2/Build_ConveyorLiftMk2.Build_ConveyorLiftMk2_C",
   "/Game/FactoryGame/Buildable/Factory/ConveyorLiftMk3/Build_ConveyorLiftMk3.Build_ConveyorLiftMk3_C",
   "/Game/FactoryGame/Buildable/Factory/ConveyorLiftMk4/Build_ConveyorLiftMk4.Build_ConveyorLiftMk4_C",
   "/Game/FactoryGame/Buildable/Factory/ConveyorLiftMk5/Build_ConveyorLiftMk5.Build_ConveyorLiftMk5_C",
+  "/Game/FactoryGame/Buildable/Factory/ConveyorLiftMk6/Build_ConveyorLiftMk6.Build_ConveyorLiftMk6_C",
 ];
 const POWER_LINE_PATHS: [&str; 2] = [
   "/Game/FactoryGame/Buildable/Factory/PowerLine/Build_PowerLine.Build_PowerLine_C",
@@ -47,15 +53,85 @@ const LOCOMOTIVE_PATHS: [&str; 1] = [
 const FREIGHT_WAGON_PATHS: [&str; 1] = [
   "/Game/FactoryGame/Buildable/Vehicle/Train/Wagon/BP_FreightWagon.BP_FreightWagon_C",
 ];
+const LIGHTWEIGHT_BUILDABLE_PATHS: [&str; 1] = [
+  "/Script/FactoryGame.FGLightweightBuildableSubsystem",
+];
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Save {
   pub header: Header,
   pub partitions: Partitions,
   pub levels: Vec<Level>,
+  /// Bytes of the body after the last level, written back as they are
+  #[serde(default)]
+  pub trailing: Vec<u8>,
+}
+
+impl Save {
+  /// Finds the level index and object index of the object with the given
+  /// instance name
+  pub fn position_of(&self, instance_name: &str) -> Option<(usize, usize)> {
+    self.levels.iter().enumerate().find_map(|(i, level)| {
+      level.position_of(instance_name).map(|j| (i, j))
+    })
+  }
+
+  /// Finds the object with the given instance name along with its header
+  pub fn find_object(&self, instance_name: &str) -> Option<(&ObjectHeader, &Object)> {
+    let (i, j) = self.position_of(instance_name)?;
+    let level = &self.levels[i];
+    Some((level.object_headers.get(j)?, level.objects.get(j)?))
+  }
+
+  /// Finds the object with the given instance name along with its header
+  pub fn find_object_mut(&mut self, instance_name: &str) -> Option<(&mut ObjectHeader, &mut Object)> {
+    let (i, j) = self.position_of(instance_name)?;
+    let level = &mut self.levels[i];
+    Some((level.object_headers.get_mut(j)?, level.objects.get_mut(j)?))
+  }
+
+  /// Iterates over every object in every level along with its header
+  pub fn iter_objects(&self) -> impl Iterator<Item = (&ObjectHeader, &Object)> {
+    self.levels.iter().flat_map(|level| level.iter_objects())
+  }
+
+  /// Indexes every object by instance name, for looking up many objects
+  /// without scanning every level each time
+  pub fn index(&self) -> SaveIndex<'_> {
+    let mut positions = HashMap::new();
+    for (i, level) in self.levels.iter().enumerate() {
+      for (j, object_header) in level.object_headers.iter().enumerate() {
+        positions.entry(object_header.get_instance_name().as_str()).or_insert((i, j));
+      }
+    }
+    SaveIndex { save: self, positions }
+  }
+}
+
+/// The level index and object index of every object in a save, keyed by
+/// instance name. Where names repeat, the first object is kept as with
+/// `Save::position_of`
+#[derive(Clone, Debug)]
+pub struct SaveIndex<'a> {
+  pub save: &'a Save,
+  positions: HashMap<&'a str, (usize, usize)>,
+}
+
+impl<'a> SaveIndex<'a> {
+  /// Same as `Save::position_of`
+  pub fn position_of(&self, instance_name: &str) -> Option<(usize, usize)> {
+    self.positions.get(instance_name).copied()
+  }
+
+  /// Same as `Save::find_object`
+  pub fn find_object(&self, instance_name: &str) -> Option<(&'a ObjectHeader, &'a Object)> {
+    let (i, j) = self.position_of(instance_name)?;
+    let level = &self.save.levels[i];
+    Some((level.object_headers.get(j)?, level.objects.get(j)?))
+  }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Header {
   pub save_header_version: i32,
   pub save_file_version: i32,
@@ -75,22 +151,55 @@ pub struct Header {
   pub is_creative_mode_enabled: i32,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+/// The number of 100-nanosecond ticks between 0001-01-01 (when .NET ticks,
+/// which `save_timestamp` is measured in, start) and the Unix epoch
+const UNIX_EPOCH_TICKS: i64 = 621_355_968_000_000_000;
+
+impl Header {
+  /// Converts `save_timestamp` into a date and time
+  pub fn saved_at(&self) -> Option<DateTime<Utc>> {
+    let ticks = self.save_timestamp - UNIX_EPOCH_TICKS;
+    DateTime::from_timestamp(ticks.div_euclid(10_000_000), (ticks.rem_euclid(10_000_000) * 100) as u32)
+  }
+}
+
+/// A grid which the world is partitioned into, with the levels in it and
+/// their hashes, in the order they're saved
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Partition {
-  pub levels: HashMap<String, u32>,
+  pub name: String,
+  pub cell_size: i32,
+  pub hash: u32,
+  pub levels: Vec<(String, u32)>,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Partitions {
-  pub partitions: HashMap<String, Partition>,
+  pub partitions: Vec<Partition>,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Level {
   pub name: String,
   pub object_headers: Vec<ObjectHeader>,
   pub collectables: Vec<Collectable>,
   pub objects: Vec<Object>,
+  /// The collectables listed again after the objects, which usually match
+  /// `collectables`
+  pub second_collectables: Vec<Collectable>,
+}
+
+impl Level {
+  /// Finds the index of the object with the given instance name, which is
+  /// the same for both `object_headers` and `objects`
+  pub fn position_of(&self, instance_name: &str) -> Option<usize> {
+    self.object_headers.iter().position(|h| h.get_instance_name() == instance_name)
+  }
+
+  /// Iterates over the level's objects along with their headers
+  pub fn iter_objects(&self) -> impl Iterator<Item = (&ObjectHeader, &Object)> {
+    self.object_headers.iter().zip(self.objects.iter())
+  }
 }
 
 pub enum ObjectType {
@@ -108,7 +217,7 @@ impl ObjectType {
   }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ObjectHeader {
   Component(ComponentHeader),
@@ -121,6 +230,7 @@ pub enum ObjectHeaderType {
   DroneTransport,
   FreightWagon,
   Game,
+  LightweightBuildables,
   Locomotive,
   PlayerState,
   PowerLine,
@@ -135,6 +245,25 @@ impl ObjectHeader {
     }
   }
 
+  pub fn get_instance_name(&self) -> &String {
+    match self {
+      ObjectHeader::Component(c) => &c.instance_name,
+      ObjectHeader::Actor(a) => &a.instance_name,
+    }
+  }
+
+  /// The class name at the end of the type path, e.g. `Build_ConstructorMk1_C`
+  pub fn get_class_name(&self) -> &str {
+    class_name(self.get_type_path())
+  }
+
+  pub fn set_instance_name(&mut self, instance_name: String) {
+    match self {
+      ObjectHeader::Component(c) => c.instance_name = instance_name,
+      ObjectHeader::Actor(a) => a.instance_name = instance_name,
+    }
+  }
+
   pub fn get_type(&self) -> Option<ObjectHeaderType> {
     let type_path = &self.get_type_path().as_str();
 
@@ -148,6 +277,8 @@ impl ObjectHeader {
       return Some(ObjectHeaderType::FreightWagon);
     } else if GAME_PATHS.contains(type_path) {
       return Some(ObjectHeaderType::Game);
+    } else if LIGHTWEIGHT_BUILDABLE_PATHS.contains(type_path) {
+      return Some(ObjectHeaderType::LightweightBuildables);
     } else if LOCOMOTIVE_PATHS.contains(type_path) {
       return Some(ObjectHeaderType::Locomotive);
     } else if PLAYER_STATE_PATHS.contains(type_path) {
@@ -162,7 +293,17 @@ impl ObjectHeader {
   }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+/// Returns the class name at the end of a path, i.e. everything after the
+/// last `.`, e.g. `Desc_IronPlate_C` for
+/// `/Game/FactoryGame/Resource/Parts/IronPlate/Desc_IronPlate.Desc_IronPlate_C`
+pub fn class_name(path: &str) -> &str {
+  match path.rfind('.') {
+    Some(i) => &path[i + 1..],
+    None => path,
+  }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ComponentHeader {
   pub type_path: String,
   pub root_object: Option<String>,
@@ -178,6 +319,31 @@ pub struct Quaternion<T> {
   pub w: T,
 }
 
+impl Quaternion<f32> {
+  /// Returns the rotation of applying `self` after `other`
+  pub fn multiply(&self, other: &Quaternion<f32>) -> Quaternion<f32> {
+    Quaternion {
+      x: self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+      y: self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+      z: self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+      w: self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+    }
+  }
+
+  /// Rotates the given vector by this (unit) quaternion
+  pub fn rotate(&self, v: &Vector<f32>) -> Vector<f32> {
+    // t = 2 * cross(q.xyz, v); v' = v + w * t + cross(q.xyz, t)
+    let tx = 2.0 * (self.y * v.z - self.z * v.y);
+    let ty = 2.0 * (self.z * v.x - self.x * v.z);
+    let tz = 2.0 * (self.x * v.y - self.y * v.x);
+    Vector {
+      x: v.x + self.w * tx + (self.y * tz - self.z * ty),
+      y: v.y + self.w * ty + (self.z * tx - self.x * tz),
+      z: v.z + self.w * tz + (self.x * ty - self.y * tx),
+    }
+  }
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Vector2D<T> {
   pub x: T,
@@ -199,7 +365,7 @@ pub struct Vector4<T> {
   pub d: T,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ActorHeader {
   pub type_path: String,
   pub root_object: Option<String>,
@@ -211,37 +377,42 @@ pub struct ActorHeader {
   pub was_placed_in_level: i32,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ComponentObject {
   pub should_be_nulled: bool, // Virtual property
   pub save_version: i32,
+  #[serde(default)]
+  pub unk_int_1: i32,
   pub size_bytes: i32,
   pub properties: Vec<Property>,
-  pub missing: Option<String>,
   pub extra: Option<ObjectExtra>,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ActorObject {
   pub should_be_nulled: bool, // Virtual property
   pub save_version: i32,
+  #[serde(default)]
+  pub unk_int_1: i32,
   pub size_bytes: i32,
   pub parent_object_root: String,
   pub parent_object_name: String,
+  /// Same as `ObjectReference::level_is_map` for the parent object
+  #[serde(default)]
+  pub parent_object_root_is_map: bool,
   pub num_components: i32,
   pub components: Vec<ObjectReference>,
   pub properties: Vec<Property>,
-  pub missing: Option<String>,
   pub extra: Option<ObjectExtra>,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct DroneTransportAction {
   pub name: String,
   pub properties: Vec<Property>,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct DroneTransport {
   pub unk_int_1: i32,
   pub unk_int_2: i32,
@@ -249,35 +420,157 @@ pub struct DroneTransport {
   pub action_queue: Vec<DroneTransportAction>,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PlayerState {
+  /// The `i32` before the ID. What it counts is unknown; it's written back
+  /// as it was read
   pub count: i32,
-  pub eos_id: Option<String>,
-  pub steam_id: Option<String>,
-  pub platform_id: Option<String>,
+  pub id: Option<PlayerId>,
+  /// Everything after the properties, kept verbatim when it doesn't write
+  /// back the same from `count` and `id`. It's written in their place as
+  /// long as they're unchanged
+  #[serde(default)]
+  pub raw: Option<Vec<u8>>,
+}
+
+/// The ID of a player on the platform they launched the game from
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PlayerId {
+  Steam(u64),
+  /// Epic Online Services IDs, as lowercase hex
+  Epic {
+    product_user_id: String,
+    account_id: Option<String>,
+  },
+  Platform(String),
+  /// An ID of a type this library doesn't know, kept as raw bytes
+  Unknown {
+    kind: u8,
+    bytes: Vec<u8>,
+  },
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Returns `None` for an odd number of digits, as the last pair is cut short
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+  (0..hex.len())
+    .step_by(2)
+    .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+    .collect()
+}
+
+impl PlayerId {
+  /// Steam IDs are a `u64` but the order of their bytes varies between
+  /// player state versions; every Steam ID of an individual account has
+  /// `0x01` as its most significant byte, which tells them apart
+  pub fn from_steam_bytes(kind: u8, bytes: &[u8]) -> PlayerId {
+    let Ok(array) = <[u8; 8]>::try_from(bytes) else {
+      return PlayerId::Unknown { kind, bytes: bytes.to_vec() };
+    };
+
+    let little = u64::from_le_bytes(array);
+    if little >> 56 == 1 {
+      PlayerId::Steam(little)
+    } else {
+      PlayerId::Steam(u64::from_be_bytes(array))
+    }
+  }
+
+  /// Epic IDs are either the 16 byte product user ID alone, or preceded by
+  /// a type byte and possibly some zero padding and followed by the account
+  /// ID
+  pub fn from_epic_bytes(kind: u8, bytes: &[u8]) -> PlayerId {
+    if bytes.len() == 16 {
+      return PlayerId::Epic { product_user_id: to_hex(bytes), account_id: None };
+    }
+
+    let start = bytes.iter().position(|b| *b != 0).map(|i| i + 1);
+    let Some(product_user_id) = start.and_then(|i| bytes.get(i..i + 16)) else {
+      return PlayerId::Unknown { kind, bytes: bytes.to_vec() };
+    };
+    let account_id = start.and_then(|i| bytes.get(i + 16..)).filter(|b| b.iter().any(|b| *b != 0));
+
+    PlayerId::Epic {
+      product_user_id: to_hex(product_user_id),
+      account_id: account_id.map(to_hex),
+    }
+  }
+
+  /// Epic IDs written as text are the product user ID and the account ID
+  /// separated by a `|`
+  pub fn from_epic_string(id: &str) -> PlayerId {
+    let mut parts = id.split('|');
+    PlayerId::Epic {
+      product_user_id: parts.next().unwrap_or_default().to_lowercase(),
+      account_id: parts.next().filter(|s| !s.is_empty()).map(|s| s.to_lowercase()),
+    }
+  }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+impl fmt::Display for PlayerId {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PlayerId::Steam(id) => write!(f, "steam:{id}"),
+      PlayerId::Epic { product_user_id, account_id: Some(account_id) } => write!(f, "epic:{product_user_id}|{account_id}"),
+      PlayerId::Epic { product_user_id, account_id: None } => write!(f, "epic:{product_user_id}"),
+      PlayerId::Platform(id) => write!(f, "platform:{id}"),
+      PlayerId::Unknown { kind, bytes } => write!(f, "unknown:{kind}:{}", to_hex(bytes)),
+    }
+  }
+}
+
+/// Parses the format written by `Display`, e.g. `steam:76561197960287930`
+impl FromStr for PlayerId {
+  type Err = ParseError;
+
+  fn from_str(s: &str) -> Result<PlayerId, ParseError> {
+    let invalid = || ParseError::InvalidPlayerId(s.to_string());
+
+    let (platform, id) = s.split_once(':').ok_or_else(invalid)?;
+    match platform {
+      "steam" => id.parse().map(PlayerId::Steam).map_err(|_| invalid()),
+      "epic" if !id.is_empty() => Ok(PlayerId::from_epic_string(id)),
+      "platform" => Ok(PlayerId::Platform(id.to_string())),
+      "unknown" => {
+        let (kind, bytes) = id.split_once(':').ok_or_else(invalid)?;
+        Ok(PlayerId::Unknown {
+          kind: kind.parse().map_err(|_| invalid())?,
+          bytes: from_hex(bytes).ok_or_else(invalid)?,
+        })
+      },
+      _ => Err(invalid()),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Circuit {
   pub id: i32,
   pub level_name: String,
   pub path_name: String,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Conveyor {
   pub length: i32,
   pub name: String,
+  #[serde(default)]
+  pub unk_str_1: String,
+  #[serde(default)]
+  pub unk_str_2: String,
   pub position: f32,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Locomotive {
   pub name: String,
   pub unk_str_1: String,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct LocomotiveExtra {
   pub count: i32,
   pub elements: Vec<Locomotive>,
@@ -285,39 +578,109 @@ pub struct LocomotiveExtra {
   pub next: ObjectReference,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PowerLine {
   pub count: i32,
   pub source: ObjectReference,
   pub target: ObjectReference,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+/// The physics state of one of a vehicle's bodies
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RigidBodyState {
+  pub position: Vector<f64>,
+  pub rotation: Quaternion<f64>,
+  pub linear_velocity: Vector<f64>,
+  pub angular_velocity: Vector<f64>,
+  pub flags: u8,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Vehicle {
   pub name: String,
-  pub unk_str_1: String,
+  pub state: RigidBodyState,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct VehicleExtra {
+  pub count: i32,
+  pub elements: Vec<Vehicle>,
+  /// Everything after the properties, kept verbatim when it couldn't be
+  /// decoded and written back in place of `elements`
+  pub raw: Option<Vec<u8>>,
+}
+
+/// A rotation, translation and scale with values as 64-bit floats
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Transform {
+  pub rotation: Quaternion<f64>,
+  pub translation: Vector<f64>,
+  pub scale: Vector<f64>,
+}
+
+/// The swatch, materials and colours applied to a buildable
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Customization {
+  pub swatch: ObjectReference,
+  pub material: ObjectReference,
+  pub pattern: ObjectReference,
+  pub skin: ObjectReference,
+  pub primary_color: Color<f32>,
+  pub secondary_color: Color<f32>,
+  pub paint_finish: ObjectReference,
+  pub pattern_rotation: u8,
+}
+
+/// A single foundation, wall, beam etc. stored by the lightweight
+/// buildable subsystem rather than as an actor
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LightweightInstance {
+  pub transform: Transform,
+  pub customization: Customization,
+  pub built_with_recipe: ObjectReference,
+  pub blueprint_proxy: ObjectReference,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LightweightBuildableClass {
+  /// The buildable's class, e.g. `Build_Foundation_8x4_01_C`, as a reference
+  pub class: ObjectReference,
+  pub instances: Vec<LightweightInstance>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LightweightBuildables {
+  pub count: i32,
+  pub classes: Vec<LightweightBuildableClass>,
+  /// Everything after the properties, kept verbatim when it couldn't be
+  /// decoded (e.g. a newer layout) and written back in place of `classes`
+  pub raw: Option<Vec<u8>>,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Extra<T> {
   pub count: i32,
   pub elements: Vec<T>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ObjectExtra {
   Circuit(Extra<Circuit>),
   Conveyor(Extra<Conveyor>),
   DroneTransport(DroneTransport),
   Game(Extra<ObjectReference>),
+  LightweightBuildables(LightweightBuildables),
   Locomotive(LocomotiveExtra),
   PlayerState(PlayerState),
   PowerLine(PowerLine),
-  Vehicle(Extra<Vehicle>),
+  Vehicle(VehicleExtra),
+  /// Everything after the properties of an object without a known extra,
+  /// kept verbatim so it's written back as it was
+  Raw(Vec<u8>),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Object {
   Component(ComponentObject),
@@ -325,6 +688,34 @@ pub enum Object {
 }
 
 impl Object {
+  pub fn get_properties(&self) -> &Vec<Property> {
+    match self {
+      Object::Actor(a) => &a.properties,
+      Object::Component(c) => &c.properties,
+    }
+  }
+
+  pub fn get_properties_mut(&mut self) -> &mut Vec<Property> {
+    match self {
+      Object::Actor(a) => &mut a.properties,
+      Object::Component(c) => &mut c.properties,
+    }
+  }
+
+  pub fn get_extra(&self) -> Option<&ObjectExtra> {
+    match self {
+      Object::Actor(a) => a.extra.as_ref(),
+      Object::Component(c) => c.extra.as_ref(),
+    }
+  }
+
+  pub fn get_extra_mut(&mut self) -> Option<&mut ObjectExtra> {
+    match self {
+      Object::Actor(a) => a.extra.as_mut(),
+      Object::Component(c) => c.extra.as_mut(),
+    }
+  }
+
   pub fn set_save_version(&mut self, save_version: i32) {
     match self {
       Object::Actor(a) => a.save_version = save_version,
@@ -332,6 +723,13 @@ impl Object {
     }
   }
 
+  pub fn set_unk_int_1(&mut self, unk_int_1: i32) {
+    match self {
+      Object::Actor(a) => a.unk_int_1 = unk_int_1,
+      Object::Component(c) => c.unk_int_1 = unk_int_1,
+    }
+  }
+
   pub fn set_size_bytes(&mut self, size_bytes: i32) {
     match self {
       Object::Actor(a) => a.size_bytes = size_bytes,
@@ -359,13 +757,6 @@ impl Object {
       Object::Component(c) => c.extra = Some(extra),
     }
   }
-
-  pub fn set_missing(&mut self, missing: String) {
-    match self {
-      Object::Actor(a) => a.missing = Some(missing),
-      Object::Component(c) => c.missing = Some(missing),
-    }
-  }
 }
 
 // This is the same as a Collectable but
@@ -373,6 +764,11 @@ impl Object {
 pub struct ObjectReference {
   pub level_name: String,
   pub path_name: String,
+  /// Whether the save named the map as the level, which the reader leaves
+  /// out of `level_name` and the writer puts back. Asset and null
+  /// references have a level name which is really empty
+  #[serde(default)]
+  pub level_is_map: bool,
 }
 
 pub type Collectable = ObjectReference;
@@ -380,6 +776,9 @@ pub type Collectable = ObjectReference;
 pub trait ObjectReferrable {
   fn set_level_name(&mut self, level_name: String);
   fn set_path_name(&mut self, path_name: String);
+
+  /// Records that the level name was the map name and so was left out
+  fn set_level_is_map(&mut self);
 }
 
 impl ObjectReferrable for ObjectReference {
@@ -387,6 +786,10 @@ impl ObjectReferrable for ObjectReference {
     self.level_name = level_name;
   }
 
+  fn set_level_is_map(&mut self) {
+    self.level_is_map = true;
+  }
+
   fn set_path_name(&mut self, path_name: String) {
     self.path_name = path_name;
   }
@@ -397,6 +800,10 @@ impl ObjectReferrable for ComponentHeader {
     self.root_object = Some(level_name);
   }
 
+  fn set_level_is_map(&mut self) {
+    self.root_object = None;
+  }
+
   fn set_path_name(&mut self, path_name: String) {
     self.instance_name = path_name;
   }
@@ -407,6 +814,10 @@ impl ObjectReferrable for ActorHeader {
     self.root_object = Some(level_name);
   }
 
+  fn set_level_is_map(&mut self) {
+    self.root_object = None;
+  }
+
   fn set_path_name(&mut self, path_name: String) {
     self.instance_name = path_name;
   }
@@ -417,6 +828,10 @@ impl ObjectReferrable for ActorObject {
     self.parent_object_root = level_name;
   }
 
+  fn set_level_is_map(&mut self) {
+    self.parent_object_root_is_map = true;
+  }
+
   fn set_path_name(&mut self, path_name: String) {
     self.parent_object_name = path_name;
   }
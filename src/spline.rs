@@ -0,0 +1,185 @@
+use serde::{Serialize, Deserialize};
+
+use crate::property::*;
+use crate::save::*;
+
+/// Steps each segment is divided into when measuring or sampling it
+const SEGMENT_STEPS: usize = 32;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SplinePoint {
+  pub location: Vector<f64>,
+  pub arrive_tangent: Vector<f64>,
+  pub leave_tangent: Vector<f64>,
+}
+
+/// The curve of a belt, pipe, track, hypertube or power line, as the
+/// Hermite spline saved in its `mSplineData`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Spline {
+  pub points: Vec<SplinePoint>,
+}
+
+fn vector(properties: &[Property], name: &str) -> Vector<f64> {
+  match find_property(properties, name).map(|p| &p.value) {
+    Some(PropertyValue::Struct((_, StructPropertyValue::DoubleVector(v)))) => v.clone(),
+    Some(PropertyValue::Struct((_, StructPropertyValue::FloatVector(v)))) => Vector { x: v.x as f64, y: v.y as f64, z: v.z as f64 },
+    _ => Vector::default(),
+  }
+}
+
+fn distance(a: &Vector<f64>, b: &Vector<f64>) -> f64 {
+  let (dx, dy, dz) = (b.x - a.x, b.y - a.y, b.z - a.z);
+  (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+fn lerp(a: &Vector<f64>, b: &Vector<f64>, t: f64) -> Vector<f64> {
+  Vector {
+    x: a.x + (b.x - a.x) * t,
+    y: a.y + (b.y - a.y) * t,
+    z: a.z + (b.z - a.z) * t,
+  }
+}
+
+/// Rotates a vector by a unit quaternion
+fn rotate(q: &Quaternion<f32>, v: &Vector<f64>) -> Vector<f64> {
+  let (x, y, z, w) = (q.x as f64, q.y as f64, q.z as f64, q.w as f64);
+  let tx = 2.0 * (y * v.z - z * v.y);
+  let ty = 2.0 * (z * v.x - x * v.z);
+  let tz = 2.0 * (x * v.y - y * v.x);
+  Vector {
+    x: v.x + w * tx + (y * tz - z * ty),
+    y: v.y + w * ty + (z * tx - x * tz),
+    z: v.z + w * tz + (x * ty - y * tx),
+  }
+}
+
+impl Spline {
+  /// Reads the `mSplineData` of an actor's properties, relative to the actor
+  pub fn from_properties(properties: &[Property]) -> Option<Spline> {
+    let Some(PropertyValue::Array(a)) = find_property(properties, "mSplineData").map(|p| &p.value) else {
+      return None;
+    };
+
+    Some(Spline {
+      points: a.elements.iter()
+        .filter_map(|e| match e {
+          ArrayPropertyValue::Struct(ArrayPropertyStructValue::Properties(point)) => Some(SplinePoint {
+            location: vector(point, "Location"),
+            arrive_tangent: vector(point, "ArriveTangent"),
+            leave_tangent: vector(point, "LeaveTangent"),
+          }),
+          _ => None,
+        })
+        .collect(),
+    })
+  }
+
+  /// The spline moved from an actor's space into the world
+  pub fn to_world(&self, actor: &ActorHeader) -> Spline {
+    let origin = Vector {
+      x: actor.position.x as f64,
+      y: actor.position.y as f64,
+      z: actor.position.z as f64,
+    };
+
+    Spline {
+      points: self.points.iter()
+        .map(|p| {
+          let location = rotate(&actor.rotation, &p.location);
+          SplinePoint {
+            location: Vector { x: location.x + origin.x, y: location.y + origin.y, z: location.z + origin.z },
+            arrive_tangent: rotate(&actor.rotation, &p.arrive_tangent),
+            leave_tangent: rotate(&actor.rotation, &p.leave_tangent),
+          }
+        })
+        .collect(),
+    }
+  }
+
+  /// Evaluates the segment starting at point `segment` at `t` from 0 to 1
+  pub fn point(&self, segment: usize, t: f64) -> Option<Vector<f64>> {
+    let p0 = self.points.get(segment)?;
+    let Some(p1) = self.points.get(segment + 1) else {
+      return Some(p0.location.clone());
+    };
+
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+    let h10 = t3 - 2.0 * t2 + t;
+    let h01 = -2.0 * t3 + 3.0 * t2;
+    let h11 = t3 - t2;
+    let (a, b, c, d) = (&p0.location, &p0.leave_tangent, &p1.location, &p1.arrive_tangent);
+    Some(Vector {
+      x: h00 * a.x + h10 * b.x + h01 * c.x + h11 * d.x,
+      y: h00 * a.y + h10 * b.y + h01 * c.y + h11 * d.y,
+      z: h00 * a.z + h10 * b.z + h01 * c.z + h11 * d.z,
+    })
+  }
+
+  /// Points along the curve, `SEGMENT_STEPS` per segment, from start to end
+  fn steps(&self) -> Vec<Vector<f64>> {
+    let mut steps = vec![];
+    for segment in 0..self.points.len().saturating_sub(1) {
+      for step in 0..SEGMENT_STEPS {
+        steps.extend(self.point(segment, step as f64 / SEGMENT_STEPS as f64));
+      }
+    }
+    steps.extend(self.points.last().map(|p| p.location.clone()));
+    steps
+  }
+
+  /// Length of the curve in world units
+  pub fn length(&self) -> f64 {
+    self.steps().windows(2).map(|w| distance(&w[0], &w[1])).sum()
+  }
+
+  /// The point `d` along the curve from its start, clamped to its ends
+  pub fn point_at_distance(&self, d: f64) -> Option<Vector<f64>> {
+    let steps = self.steps();
+    let mut travelled = 0.0;
+    for w in steps.windows(2) {
+      let step = distance(&w[0], &w[1]);
+      if travelled + step >= d {
+        let t = if step > 0.0 { ((d - travelled) / step).max(0.0) } else { 0.0 };
+        return Some(lerp(&w[0], &w[1], t));
+      }
+      travelled += step;
+    }
+    steps.last().cloned()
+  }
+
+  /// The corners of the smallest axis-aligned box around the curve
+  pub fn bounding_box(&self) -> Option<(Vector<f64>, Vector<f64>)> {
+    let steps = self.steps();
+    let first = steps.first()?;
+    Some(steps.iter().fold((first.clone(), first.clone()), |(min, max), p| (
+      Vector { x: min.x.min(p.x), y: min.y.min(p.y), z: min.z.min(p.z) },
+      Vector { x: max.x.max(p.x), y: max.y.max(p.y), z: max.z.max(p.z) },
+    )))
+  }
+
+  /// Points every `spacing` units along the curve, including both ends
+  pub fn sample(&self, spacing: f64) -> Vec<Vector<f64>> {
+    let length = self.length();
+    if spacing <= 0.0 || length == 0.0 {
+      return self.points.first().map(|p| vec![p.location.clone()]).unwrap_or_default();
+    }
+
+    let count = (length / spacing).ceil() as usize;
+    (0..=count)
+      .filter_map(|i| self.point_at_distance((i as f64 * spacing).min(length)))
+      .collect()
+  }
+}
+
+impl Save {
+  /// Returns the curve of an actor in world space, if it has one
+  pub fn spline(&self, instance_name: &str) -> Option<Spline> {
+    match self.find_object(instance_name)? {
+      (ObjectHeader::Actor(actor), object) => Some(Spline::from_properties(object.get_properties())?.to_world(actor)),
+      _ => None,
+    }
+  }
+}
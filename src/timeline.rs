@@ -0,0 +1,182 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::warn;
+use serde::{Serialize, Deserialize};
+
+use crate::property::*;
+use crate::save::*;
+use crate::{read_file, read_file_header, Result};
+
+const POWER_INFO_PATH: &str = "/Script/FactoryGame.FGPowerInfoComponent";
+const RESOURCE_SINK_SUBSYSTEM_PATH: &str = "/Game/FactoryGame/Buildable/Factory/ResourceSink/BP_ResourceSinkSubsystem.BP_ResourceSinkSubsystem_C";
+
+/// A save file on disk along with its header
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SaveFile {
+  pub path: PathBuf,
+  pub header: Header,
+}
+
+/// Every save of one session, oldest first
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Session {
+  pub name: String,
+  pub saves: Vec<SaveFile>,
+}
+
+/// Reads the header of every `.sav` file in a directory and groups them by
+/// session name, ordering each session's saves by when they were saved.
+/// Files which can't be read are skipped with a warning
+pub fn find_sessions<P: AsRef<Path>>(dir: P) -> Result<Vec<Session>> {
+  let mut sessions: HashMap<String, Vec<SaveFile>> = HashMap::new();
+
+  for entry in fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.extension().and_then(|e| e.to_str()) != Some("sav") {
+      continue;
+    }
+
+    match read_file_header(&path) {
+      Ok(header) => sessions.entry(header.session_name.clone()).or_default().push(SaveFile { path, header }),
+      Err(e) => warn!("Skipping {}: {e}", path.display()),
+    }
+  }
+
+  let mut sessions: Vec<Session> = sessions.into_iter()
+    .map(|(name, mut saves)| {
+      saves.sort_by_key(|s| (s.header.save_timestamp, s.header.played_seconds));
+      Session { name, saves }
+    })
+    .collect();
+  sessions.sort_by(|a, b| a.name.cmp(&b.name));
+
+  Ok(sessions)
+}
+
+/// Which optional metrics to extract from each save
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MetricOptions {
+  /// Items whose total across every inventory and the dimensional depot
+  /// should be counted, either as class names (`Desc_IronPlate_C`) or full
+  /// paths
+  pub items: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Metrics {
+  /// Number of objects of each class, e.g. `Build_ConstructorMk1_C`
+  pub object_counts: BTreeMap<String, usize>,
+  /// Number of objects in each category, e.g. `Buildable/Factory`
+  pub category_counts: BTreeMap<String, usize>,
+  /// Total production capacity in MW
+  pub power_production: f64,
+  /// Total target consumption in MW
+  pub power_consumption: f64,
+  pub sink_points: i64,
+  /// Totals of the items chosen in `MetricOptions::items`
+  pub items: BTreeMap<String, i64>,
+}
+
+/// The category of an object is the two directories following
+/// `/Game/FactoryGame/` in its type path, or its script module for native
+/// classes (e.g. `Script/FactoryGame`)
+fn category(type_path: &str) -> String {
+  match type_path.strip_prefix("/Game/FactoryGame/") {
+    Some(rest) => rest.split('/').take(2).filter(|s| !s.contains('.')).collect::<Vec<_>>().join("/"),
+    None => type_path.trim_start_matches('/').split('.').next().unwrap_or_default().to_string(),
+  }
+}
+
+/// Extracts the metrics of a single save
+pub fn extract_metrics(save: &Save, options: &MetricOptions) -> Metrics {
+  let mut metrics = Metrics::default();
+
+  for (object_header, object) in save.iter_objects() {
+    *metrics.object_counts.entry(object_header.get_class_name().to_string()).or_default() += 1;
+    *metrics.category_counts.entry(category(object_header.get_type_path())).or_default() += 1;
+
+    let properties = object.get_properties();
+    match object_header.get_type_path().as_str() {
+      POWER_INFO_PATH => {
+        metrics.power_production += property_f64(properties, "mBaseProduction").unwrap_or(0.0);
+        metrics.power_production += property_f64(properties, "mDynamicProductionCapacity").unwrap_or(0.0);
+        metrics.power_consumption += property_f64(properties, "mTargetConsumption").unwrap_or(0.0);
+      },
+      RESOURCE_SINK_SUBSYSTEM_PATH => {
+        if let Some(PropertyValue::Array(a)) = find_property(properties, "mTotalResourceSinkPoints").map(|p| &p.value) {
+          for element in &a.elements {
+            if let ArrayPropertyValue::Long(points) = element {
+              metrics.sink_points += points;
+            }
+          }
+        }
+      },
+      _ => {},
+    }
+  }
+
+  if !options.items.is_empty() {
+    // Counted the same way as `Save::locate_item`, but from one pass over
+    // the inventories
+    let totals = save.item_totals();
+    metrics.items = options.items.iter()
+      .map(|item| {
+        let total = totals.iter()
+          .filter(|(path, _)| *path == item || class_name(path) == item)
+          .map(|(_, count)| count)
+          .sum();
+        (item.clone(), total)
+      })
+      .collect();
+  }
+
+  metrics
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimelinePoint {
+  pub path: PathBuf,
+  pub played_seconds: i32,
+  pub save_timestamp: i64,
+  pub metrics: Metrics,
+}
+
+/// The metrics of every save of a session in the order they were saved
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Timeline {
+  pub session_name: String,
+  pub points: Vec<TimelinePoint>,
+}
+
+impl Timeline {
+  /// Reads every save of the session one at a time and extracts its metrics.
+  /// Saves which fail to parse are skipped with a warning
+  pub fn from_session(session: &Session, options: &MetricOptions) -> Timeline {
+    let mut points: Vec<TimelinePoint> = vec![];
+
+    for save_file in &session.saves {
+      match read_file(&save_file.path) {
+        Ok(save) => points.push(TimelinePoint {
+          path: save_file.path.clone(),
+          played_seconds: save_file.header.played_seconds,
+          save_timestamp: save_file.header.save_timestamp,
+          metrics: extract_metrics(&save, options),
+        }),
+        Err(e) => warn!("Skipping {}: {e}", save_file.path.display()),
+      }
+    }
+
+    Timeline {
+      session_name: session.name.clone(),
+      points,
+    }
+  }
+
+  /// Picks a single value out of each point's metrics, paired with the
+  /// played time in seconds, e.g. for plotting power production over time
+  pub fn series<F: Fn(&Metrics) -> f64>(&self, f: F) -> Vec<(i32, f64)> {
+    self.points.iter().map(|p| (p.played_seconds, f(&p.metrics))).collect()
+  }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashSet;
+
+use serde::{Serialize, Deserialize};
+
+use crate::inventory::Inventory;
+use crate::property::*;
+use crate::save::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VehicleKind {
+  Tractor,
+  Truck,
+  Explorer,
+  Cyberwagon,
+  FactoryCart,
+  GoldenFactoryCart,
+}
+
+impl VehicleKind {
+  pub fn from_class_name(class_name: &str) -> Option<VehicleKind> {
+    match class_name {
+      "BP_Tractor_C" => Some(VehicleKind::Tractor),
+      "BP_Truck_C" => Some(VehicleKind::Truck),
+      "BP_Explorer_C" => Some(VehicleKind::Explorer),
+      "Testa_BP_WB_C" => Some(VehicleKind::Cyberwagon),
+      "BP_Golfcart_C" => Some(VehicleKind::FactoryCart),
+      "BP_GolfcartGold_C" => Some(VehicleKind::GoldenFactoryCart),
+      _ => None,
+    }
+  }
+}
+
+/// A point of a recorded path
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Waypoint {
+  pub instance_name: String,
+  pub position: Vector<f32>,
+  pub rotation: Quaternion<f32>,
+  /// Speed the vehicle drove at when the point was recorded
+  pub speed: Option<i32>,
+  /// Seconds to wait at the point
+  pub wait_time: Option<f32>,
+}
+
+/// A path recorded for self-driving vehicles, held by an
+/// `FGDrivingTargetList`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DrivingPath {
+  pub instance_name: String,
+  pub name: Option<String>,
+  pub waypoints: Vec<Waypoint>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VehicleInfo {
+  pub instance_name: String,
+  pub kind: VehicleKind,
+  pub position: Vector<f32>,
+  pub rotation: Quaternion<f32>,
+  pub fuel: Option<Inventory>,
+  pub cargo: Option<Inventory>,
+  pub is_self_driving: bool,
+  pub path: Option<DrivingPath>,
+  /// The physics state of each of the vehicle's bodies
+  pub bodies: Vec<Vehicle>,
+}
+
+impl Save {
+  /// Returns every wheeled vehicle in the save
+  pub fn vehicles(&self) -> Vec<VehicleInfo> {
+    let index = self.index();
+    self.iter_objects()
+      .filter(|(object_header, _)| matches!(object_header.get_type(), Some(ObjectHeaderType::Vehicle)))
+      .filter_map(|(object_header, object)| {
+        let ObjectHeader::Actor(actor) = object_header else {
+          return None;
+        };
+        let kind = VehicleKind::from_class_name(object_header.get_class_name())?;
+        let properties = object.get_properties();
+
+        Some(VehicleInfo {
+          instance_name: actor.instance_name.clone(),
+          kind,
+          position: actor.position.clone(),
+          rotation: actor.rotation.clone(),
+          fuel: property_reference(properties, "mFuelInventory").and_then(|name| index.inventory(name)),
+          cargo: property_reference(properties, "mStorageInventory").and_then(|name| index.inventory(name)),
+          is_self_driving: property_bool(properties, "mIsSelfDriving").unwrap_or(false),
+          path: property_reference(properties, "mTargetList").and_then(|name| index.driving_path(name)),
+          bodies: match object.get_extra() {
+            Some(ObjectExtra::Vehicle(e)) => e.elements.clone(),
+            _ => vec![],
+          },
+        })
+      })
+      .collect()
+  }
+
+  /// Reads a recorded path, either from its list of targets or by following
+  /// each target's link to the next one
+  pub fn driving_path(&self, instance_name: &str) -> Option<DrivingPath> {
+    self.index().driving_path(instance_name)
+  }
+}
+
+impl SaveIndex<'_> {
+  /// Same as `Save::driving_path`
+  pub fn driving_path(&self, instance_name: &str) -> Option<DrivingPath> {
+    let (_, list) = self.find_object(instance_name)?;
+    let properties = list.get_properties();
+
+    let targets: Vec<String> = match find_property(properties, "mTargets").map(|p| &p.value) {
+      Some(PropertyValue::Array(a)) => a.elements.iter()
+        .filter_map(|e| match e {
+          ArrayPropertyValue::Object(o) if !o.path_name.is_empty() => Some(o.path_name.clone()),
+          _ => None,
+        })
+        .collect(),
+      _ => {
+        let mut targets = vec![];
+        let mut seen: HashSet<&String> = HashSet::new();
+        let mut next = property_reference(properties, "mFirstTarget");
+        // The path is a loop, so stop once a target comes round again
+        while let Some(name) = next.filter(|name| seen.insert(name)) {
+          targets.push(name.clone());
+          next = self.find_object(name).and_then(|(_, target)| property_reference(target.get_properties(), "mNext"));
+        }
+        targets
+      },
+    };
+
+    Some(DrivingPath {
+      instance_name: instance_name.to_string(),
+      name: match find_property(properties, "mPathName").map(|p| &p.value) {
+        Some(PropertyValue::String(s)) => Some(s.clone()),
+        _ => None,
+      },
+      waypoints: targets.iter()
+        .filter_map(|name| match self.find_object(name)? {
+          (ObjectHeader::Actor(actor), target) => {
+            let properties = target.get_properties();
+            Some(Waypoint {
+              instance_name: actor.instance_name.clone(),
+              position: actor.position.clone(),
+              rotation: actor.rotation.clone(),
+              speed: property_int(properties, "mTargetSpeed"),
+              wait_time: property_f32(properties, "mWaitTime"),
+            })
+          },
+          _ => None,
+        })
+        .collect(),
+    })
+  }
+}
@@ -0,0 +1,1028 @@
+use std::fmt;
+use std::mem;
+
+use crate::property::*;
+use crate::save::*;
+
+/// A single step taken while descending from a save into its property tree
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathSegment {
+  Level(String),
+  Object(String),
+  Property(String, i32),
+  Index(usize),
+  MapKey(usize),
+  MapValue(usize),
+  Field(&'static str),
+}
+
+/// The location of the node currently being visited, e.g.
+/// `Persistent_Level/Build_StorageContainerMk1_C_1.mInventoryStacks[0]`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VisitPath {
+  segments: Vec<PathSegment>,
+}
+
+impl VisitPath {
+  pub fn new() -> VisitPath {
+    VisitPath::default()
+  }
+
+  pub fn segments(&self) -> &[PathSegment] {
+    &self.segments
+  }
+
+  pub fn push(&mut self, segment: PathSegment) {
+    self.segments.push(segment);
+  }
+
+  pub fn pop(&mut self) -> Option<PathSegment> {
+    self.segments.pop()
+  }
+
+  /// The name of the level the visited node belongs to, if any
+  pub fn level_name(&self) -> Option<&str> {
+    self.segments.iter().find_map(|s| match s {
+      PathSegment::Level(name) => Some(name.as_str()),
+      _ => None,
+    })
+  }
+
+  /// The instance name of the object the visited node belongs to, if any
+  pub fn instance_name(&self) -> Option<&str> {
+    self.segments.iter().find_map(|s| match s {
+      PathSegment::Object(name) => Some(name.as_str()),
+      _ => None,
+    })
+  }
+
+  /// The name of the innermost property enclosing the visited node, if any
+  pub fn property_name(&self) -> Option<&str> {
+    self.segments.iter().rev().find_map(|s| match s {
+      PathSegment::Property(name, _) => Some(name.as_str()),
+      _ => None,
+    })
+  }
+}
+
+impl fmt::Display for VisitPath {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for segment in &self.segments {
+      match segment {
+        PathSegment::Level(name) => write!(f, "{name}")?,
+        PathSegment::Object(name) => write!(f, "/{name}")?,
+        PathSegment::Property(name, 0) => write!(f, ".{name}")?,
+        PathSegment::Property(name, index) => write!(f, ".{name}({index})")?,
+        PathSegment::Index(i) => write!(f, "[{i}]")?,
+        PathSegment::MapKey(i) => write!(f, "[key {i}]")?,
+        PathSegment::MapValue(i) => write!(f, "[{i}]")?,
+        PathSegment::Field(name) => write!(f, ".{name}")?,
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Read-only traversal over a save and its property tree. Every method
+/// defaults to the matching `walk_*` function so implementors only need to
+/// override the nodes they care about, calling the `walk_*` function
+/// themselves to keep descending
+pub trait Visitor {
+  fn visit_save(&mut self, save: &Save, path: &mut VisitPath) {
+    walk_save(self, save, path)
+  }
+
+  fn visit_level(&mut self, level: &Level, path: &mut VisitPath) {
+    walk_level(self, level, path)
+  }
+
+  fn visit_object_header(&mut self, _object_header: &ObjectHeader, _path: &mut VisitPath) {}
+
+  fn visit_object(&mut self, object: &Object, path: &mut VisitPath) {
+    walk_object(self, object, path)
+  }
+
+  fn visit_object_extra(&mut self, extra: &ObjectExtra, path: &mut VisitPath) {
+    walk_object_extra(self, extra, path)
+  }
+
+  fn visit_property(&mut self, property: &Property, path: &mut VisitPath) {
+    walk_property(self, property, path)
+  }
+
+  fn visit_property_value(&mut self, value: &PropertyValue, path: &mut VisitPath) {
+    walk_property_value(self, value, path)
+  }
+
+  fn visit_array_value(&mut self, value: &ArrayPropertyValue, path: &mut VisitPath) {
+    walk_array_value(self, value, path)
+  }
+
+  fn visit_array_struct_value(&mut self, value: &ArrayPropertyStructValue, path: &mut VisitPath) {
+    walk_array_struct_value(self, value, path)
+  }
+
+  fn visit_map_key(&mut self, key: &MapPropertyKey, path: &mut VisitPath) {
+    walk_map_key(self, key, path)
+  }
+
+  fn visit_map_value(&mut self, value: &MapPropertyValue, path: &mut VisitPath) {
+    walk_map_value(self, value, path)
+  }
+
+  fn visit_set_value(&mut self, value: &SetPropertyValue, path: &mut VisitPath) {
+    walk_set_value(self, value, path)
+  }
+
+  fn visit_struct_value(&mut self, value: &StructPropertyValue, path: &mut VisitPath) {
+    walk_struct_value(self, value, path)
+  }
+
+  fn visit_text(&mut self, text: &TextProperty, path: &mut VisitPath) {
+    walk_text(self, text, path)
+  }
+
+  fn visit_fin_network_trace(&mut self, trace: &FINNetworkTrace, path: &mut VisitPath) {
+    walk_fin_network_trace(self, trace, path)
+  }
+
+  fn visit_fin_lua_processor_state_storage(&mut self, storage: &FINLuaProcessorStateStorage, path: &mut VisitPath) {
+    walk_fin_lua_processor_state_storage(self, storage, path)
+  }
+
+  /// Called for every reference to another object, including those stored as
+  /// a separate level name and path name pair (e.g. `InventoryItem`)
+  fn visit_object_reference(&mut self, _reference: &ObjectReference, _path: &mut VisitPath) {}
+
+  /// Called for every free-form string value (string properties, names and
+  /// text history values)
+  fn visit_string(&mut self, _value: &str, _path: &mut VisitPath) {}
+}
+
+pub fn walk_save<V: Visitor + ?Sized>(visitor: &mut V, save: &Save, path: &mut VisitPath) {
+  for level in &save.levels {
+    path.push(PathSegment::Level(level.name.clone()));
+    visitor.visit_level(level, path);
+    path.pop();
+  }
+}
+
+pub fn walk_level<V: Visitor + ?Sized>(visitor: &mut V, level: &Level, path: &mut VisitPath) {
+  for object_header in &level.object_headers {
+    path.push(PathSegment::Object(object_header.get_instance_name().clone()));
+    visitor.visit_object_header(object_header, path);
+    path.pop();
+  }
+
+  path.push(PathSegment::Field("collectables"));
+  for (i, collectable) in level.collectables.iter().enumerate() {
+    path.push(PathSegment::Index(i));
+    visitor.visit_object_reference(collectable, path);
+    path.pop();
+  }
+  path.pop();
+
+  path.push(PathSegment::Field("second_collectables"));
+  for (i, collectable) in level.second_collectables.iter().enumerate() {
+    path.push(PathSegment::Index(i));
+    visitor.visit_object_reference(collectable, path);
+    path.pop();
+  }
+  path.pop();
+
+  for (i, object) in level.objects.iter().enumerate() {
+    let name = match level.object_headers.get(i) {
+      Some(h) => h.get_instance_name().clone(),
+      None => i.to_string(),
+    };
+    path.push(PathSegment::Object(name));
+    visitor.visit_object(object, path);
+    path.pop();
+  }
+}
+
+pub fn walk_object<V: Visitor + ?Sized>(visitor: &mut V, object: &Object, path: &mut VisitPath) {
+  let (properties, extra) = match object {
+    Object::Actor(a) => {
+      path.push(PathSegment::Field("parent"));
+      visitor.visit_object_reference(&ObjectReference {
+        level_name: a.parent_object_root.clone(),
+        path_name: a.parent_object_name.clone(),
+        level_is_map: a.parent_object_root_is_map,
+      }, path);
+      path.pop();
+
+      path.push(PathSegment::Field("components"));
+      for (i, component) in a.components.iter().enumerate() {
+        path.push(PathSegment::Index(i));
+        visitor.visit_object_reference(component, path);
+        path.pop();
+      }
+      path.pop();
+
+      (&a.properties, &a.extra)
+    },
+    Object::Component(c) => (&c.properties, &c.extra),
+  };
+
+  for property in properties {
+    visitor.visit_property(property, path);
+  }
+
+  if let Some(extra) = extra {
+    path.push(PathSegment::Field("extra"));
+    visitor.visit_object_extra(extra, path);
+    path.pop();
+  }
+}
+
+pub fn walk_object_extra<V: Visitor + ?Sized>(visitor: &mut V, extra: &ObjectExtra, path: &mut VisitPath) {
+  match extra {
+    ObjectExtra::Circuit(e) => {
+      for (i, circuit) in e.elements.iter().enumerate() {
+        path.push(PathSegment::Index(i));
+        visitor.visit_object_reference(&ObjectReference {
+          level_name: circuit.level_name.clone(),
+          path_name: circuit.path_name.clone(),
+          level_is_map: false,
+        }, path);
+        path.pop();
+      }
+    },
+    ObjectExtra::DroneTransport(e) => {
+      for (field, actions) in [("active_action", &e.active_action), ("action_queue", &e.action_queue)] {
+        path.push(PathSegment::Field(field));
+        for (i, action) in actions.iter().enumerate() {
+          path.push(PathSegment::Index(i));
+          for property in &action.properties {
+            visitor.visit_property(property, path);
+          }
+          path.pop();
+        }
+        path.pop();
+      }
+    },
+    ObjectExtra::Game(e) => {
+      for (i, reference) in e.elements.iter().enumerate() {
+        path.push(PathSegment::Index(i));
+        visitor.visit_object_reference(reference, path);
+        path.pop();
+      }
+    },
+    ObjectExtra::LightweightBuildables(e) => {
+      path.push(PathSegment::Field("classes"));
+      for (i, class) in e.classes.iter().enumerate() {
+        path.push(PathSegment::Index(i));
+        path.push(PathSegment::Field("class"));
+        visitor.visit_object_reference(&class.class, path);
+        path.pop();
+        path.push(PathSegment::Field("instances"));
+        for (j, instance) in class.instances.iter().enumerate() {
+          path.push(PathSegment::Index(j));
+          let customization = &instance.customization;
+          for (field, reference) in [
+            ("swatch", &customization.swatch),
+            ("material", &customization.material),
+            ("pattern", &customization.pattern),
+            ("skin", &customization.skin),
+            ("paint_finish", &customization.paint_finish),
+          ] {
+            path.push(PathSegment::Field(field));
+            visitor.visit_object_reference(reference, path);
+            path.pop();
+          }
+          path.push(PathSegment::Field("built_with_recipe"));
+          visitor.visit_object_reference(&instance.built_with_recipe, path);
+          path.pop();
+          path.push(PathSegment::Field("blueprint_proxy"));
+          visitor.visit_object_reference(&instance.blueprint_proxy, path);
+          path.pop();
+          path.pop();
+        }
+        path.pop();
+        path.pop();
+      }
+      path.pop();
+    },
+    ObjectExtra::Locomotive(e) => {
+      path.push(PathSegment::Field("prev"));
+      visitor.visit_object_reference(&e.prev, path);
+      path.pop();
+      path.push(PathSegment::Field("next"));
+      visitor.visit_object_reference(&e.next, path);
+      path.pop();
+    },
+    ObjectExtra::PowerLine(e) => {
+      path.push(PathSegment::Field("source"));
+      visitor.visit_object_reference(&e.source, path);
+      path.pop();
+      path.push(PathSegment::Field("target"));
+      visitor.visit_object_reference(&e.target, path);
+      path.pop();
+    },
+    ObjectExtra::Conveyor(_) | ObjectExtra::PlayerState(_) | ObjectExtra::Vehicle(_) | ObjectExtra::Raw(_) => {},
+  }
+}
+
+pub fn walk_property<V: Visitor + ?Sized>(visitor: &mut V, property: &Property, path: &mut VisitPath) {
+  path.push(PathSegment::Property(property.name.clone(), property.index));
+  visitor.visit_property_value(&property.value, path);
+  path.pop();
+}
+
+pub fn walk_property_value<V: Visitor + ?Sized>(visitor: &mut V, value: &PropertyValue, path: &mut VisitPath) {
+  match value {
+    PropertyValue::String(s) => visitor.visit_string(s, path),
+    PropertyValue::Object(o) => visitor.visit_object_reference(o, path),
+    PropertyValue::Byte(b) => {
+      if let Some(s) = &b.string_value {
+        visitor.visit_string(s, path);
+      }
+    },
+    PropertyValue::Text(t) => visitor.visit_text(t, path),
+    PropertyValue::Array(a) => {
+      for (i, element) in a.elements.iter().enumerate() {
+        path.push(PathSegment::Index(i));
+        visitor.visit_array_value(element, path);
+        path.pop();
+      }
+    },
+    PropertyValue::Map(m) => {
+      for (i, key) in m.keys.iter().enumerate() {
+        path.push(PathSegment::MapKey(i));
+        visitor.visit_map_key(key, path);
+        path.pop();
+      }
+      for (i, value) in m.values.iter().enumerate() {
+        path.push(PathSegment::MapValue(i));
+        visitor.visit_map_value(value, path);
+        path.pop();
+      }
+    },
+    PropertyValue::Set(s) => {
+      for (i, value) in s.values.iter().enumerate() {
+        path.push(PathSegment::Index(i));
+        visitor.visit_set_value(value, path);
+        path.pop();
+      }
+    },
+    PropertyValue::Struct((_, s)) => visitor.visit_struct_value(s, path),
+    PropertyValue::Bool(_) | PropertyValue::Int8(_) | PropertyValue::Int(_) |
+    PropertyValue::UInt32(_) | PropertyValue::Int64(_) | PropertyValue::UInt64(_) |
+    PropertyValue::Float(_) | PropertyValue::Double(_) | PropertyValue::Enum(_) => {},
+  }
+}
+
+pub fn walk_array_value<V: Visitor + ?Sized>(visitor: &mut V, value: &ArrayPropertyValue, path: &mut VisitPath) {
+  match value {
+    ArrayPropertyValue::Enum(s) | ArrayPropertyValue::Str(s) => visitor.visit_string(s, path),
+    ArrayPropertyValue::Text(t) => visitor.visit_text(t, path),
+    ArrayPropertyValue::Object(o) => visitor.visit_object_reference(o, path),
+    ArrayPropertyValue::Struct(s) => visitor.visit_array_struct_value(s, path),
+    ArrayPropertyValue::Byte(_) | ArrayPropertyValue::Bytes(_) | ArrayPropertyValue::Bool(_) | ArrayPropertyValue::Int(_) |
+    ArrayPropertyValue::Long(_) | ArrayPropertyValue::Float(_) | ArrayPropertyValue::SoftObject(_) => {},
+  }
+}
+
+pub fn walk_array_struct_value<V: Visitor + ?Sized>(visitor: &mut V, value: &ArrayPropertyStructValue, path: &mut VisitPath) {
+  match value {
+    ArrayPropertyStructValue::InventoryItem(item) => {
+      visitor.visit_string(&item.item_name, path);
+      visitor.visit_object_reference(&ObjectReference {
+        level_name: item.level_name.clone(),
+        path_name: item.path_name.clone(),
+        level_is_map: false,
+      }, path);
+    },
+    ArrayPropertyStructValue::FINNetworkTrace(t) => visitor.visit_fin_network_trace(t, path),
+    ArrayPropertyStructValue::Properties(properties) => {
+      for property in properties {
+        visitor.visit_property(property, path);
+      }
+    },
+    ArrayPropertyStructValue::GUID(_) | ArrayPropertyStructValue::Vector(_) |
+    ArrayPropertyStructValue::LinearColor(_) | ArrayPropertyStructValue::FINGPUT1BufferPixel(_) |
+    ArrayPropertyStructValue::None => {},
+  }
+}
+
+pub fn walk_map_key<V: Visitor + ?Sized>(visitor: &mut V, key: &MapPropertyKey, path: &mut VisitPath) {
+  match key {
+    MapPropertyKey::String(s) => visitor.visit_string(s, path),
+    MapPropertyKey::Object(o) => visitor.visit_object_reference(o, path),
+    MapPropertyKey::Properties(properties) => {
+      for property in properties {
+        visitor.visit_property(property, path);
+      }
+    },
+    MapPropertyKey::Int(_) | MapPropertyKey::Long(_) | MapPropertyKey::IntVector(_) |
+    MapPropertyKey::FloatVector(_) | MapPropertyKey::DoubleVector(_) => {},
+  }
+}
+
+pub fn walk_map_value<V: Visitor + ?Sized>(visitor: &mut V, value: &MapPropertyValue, path: &mut VisitPath) {
+  match value {
+    MapPropertyValue::String(s) => visitor.visit_string(s, path),
+    MapPropertyValue::Text(t) => visitor.visit_text(t, path),
+    MapPropertyValue::Object(o) => visitor.visit_object_reference(o, path),
+    MapPropertyValue::Struct(properties) => {
+      for property in properties {
+        visitor.visit_property(property, path);
+      }
+    },
+    MapPropertyValue::Byte(_) | MapPropertyValue::Bool(_) | MapPropertyValue::Int(_) |
+    MapPropertyValue::Long(_) | MapPropertyValue::Float(_) | MapPropertyValue::Double(_) => {},
+  }
+}
+
+pub fn walk_set_value<V: Visitor + ?Sized>(visitor: &mut V, value: &SetPropertyValue, path: &mut VisitPath) {
+  match value {
+    SetPropertyValue::String(s) => visitor.visit_string(s, path),
+    SetPropertyValue::Object(o) => visitor.visit_object_reference(o, path),
+    SetPropertyValue::FINNetworkTrace(t) => visitor.visit_fin_network_trace(t, path),
+    SetPropertyValue::Int(_) | SetPropertyValue::UInt32(_) | SetPropertyValue::Vector(_) => {},
+  }
+}
+
+pub fn walk_struct_value<V: Visitor + ?Sized>(visitor: &mut V, value: &StructPropertyValue, path: &mut VisitPath) {
+  match value {
+    StructPropertyValue::RailroadTrackPosition(p) => {
+      path.push(PathSegment::Field("object"));
+      visitor.visit_object_reference(&p.object, path);
+      path.pop();
+    },
+    StructPropertyValue::InventoryItem(item) => {
+      path.push(PathSegment::Field("item_name"));
+      visitor.visit_string(&item.item_name, path);
+      path.pop();
+      path.push(PathSegment::Field("object"));
+      visitor.visit_object_reference(&item.object, path);
+      path.pop();
+      visitor.visit_property(&item.property, path);
+    },
+    StructPropertyValue::FINNetworkTrace(t) => visitor.visit_fin_network_trace(t, path),
+    StructPropertyValue::FINLuaProcessorStateStorage(s) => visitor.visit_fin_lua_processor_state_storage(s, path),
+    StructPropertyValue::Properties(properties) => {
+      for property in properties {
+        visitor.visit_property(property, path);
+      }
+    },
+    StructPropertyValue::Color(_) | StructPropertyValue::LinearColor(_) |
+    StructPropertyValue::FloatVector(_) | StructPropertyValue::DoubleVector(_) |
+    StructPropertyValue::IntVector2D(_) | StructPropertyValue::FloatVector2D(_) |
+    StructPropertyValue::DoubleVector2D(_) | StructPropertyValue::IntVector4(_) |
+    StructPropertyValue::DoubleVector4(_) | StructPropertyValue::FloatQuaternion(_) |
+    StructPropertyValue::DoubleQuaternion(_) | StructPropertyValue::Box(_) |
+    StructPropertyValue::TimerHandle(_) | StructPropertyValue::GUID(_) |
+    StructPropertyValue::FluidBox(_) | StructPropertyValue::SlateBrush(_) |
+    StructPropertyValue::DateTime(_) | StructPropertyValue::FICFrameRange(_) |
+    StructPropertyValue::IntPoint(_) | StructPropertyValue::None => {},
+  }
+}
+
+pub fn walk_text<V: Visitor + ?Sized>(visitor: &mut V, text: &TextProperty, path: &mut VisitPath) {
+  match &text.value {
+    TextPropertyHistory::BaseHistory(h) => visitor.visit_string(&h.value, path),
+    TextPropertyHistory::ArgumentHistory(h) => {
+      path.push(PathSegment::Field("source_format"));
+      visitor.visit_text(&h.source_format, path);
+      path.pop();
+      path.push(PathSegment::Field("arguments"));
+      for (i, argument) in h.arguments.iter().enumerate() {
+        path.push(PathSegment::Index(i));
+        visitor.visit_text(&argument.value, path);
+        path.pop();
+      }
+      path.pop();
+    },
+    TextPropertyHistory::TransformHistory(h) => visitor.visit_text(&h.source_text, path),
+    TextPropertyHistory::NoneHistory(h) => visitor.visit_string(&h.value, path),
+    TextPropertyHistory::StringTableEntryHistory(_) | TextPropertyHistory::None => {},
+  }
+}
+
+pub fn walk_fin_network_trace<V: Visitor + ?Sized>(visitor: &mut V, trace: &FINNetworkTrace, path: &mut VisitPath) {
+  visitor.visit_object_reference(&ObjectReference {
+    level_name: trace.level_name.clone(),
+    path_name: trace.path_name.clone(),
+    level_is_map: false,
+  }, path);
+
+  if let Some(prev) = &trace.prev {
+    path.push(PathSegment::Field("prev"));
+    visitor.visit_fin_network_trace(prev, path);
+    path.pop();
+  }
+}
+
+pub fn walk_fin_lua_processor_state_storage<V: Visitor + ?Sized>(visitor: &mut V, storage: &FINLuaProcessorStateStorage, path: &mut VisitPath) {
+  path.push(PathSegment::Field("trace"));
+  for (i, trace) in storage.trace.iter().enumerate() {
+    path.push(PathSegment::Index(i));
+    visitor.visit_fin_network_trace(trace, path);
+    path.pop();
+  }
+  path.pop();
+
+  path.push(PathSegment::Field("reference"));
+  for (i, reference) in storage.reference.iter().enumerate() {
+    path.push(PathSegment::Index(i));
+    visitor.visit_object_reference(reference, path);
+    path.pop();
+  }
+  path.pop();
+
+  path.push(PathSegment::Field("structs"));
+  for (i, s) in storage.structs.iter().enumerate() {
+    path.push(PathSegment::Index(i));
+    match &s.value {
+      FINLuaProcessorStateStorageStructValue::InventoryStack(stack) => {
+        visitor.visit_struct_value(&stack.unk_struct_1.1, path);
+      },
+      FINLuaProcessorStateStorageStructValue::FINTrackGraph(trace, _) => {
+        visitor.visit_fin_network_trace(trace, path);
+      },
+      FINLuaProcessorStateStorageStructValue::Vector(_) |
+      FINLuaProcessorStateStorageStructValue::LinearColor(_) |
+      FINLuaProcessorStateStorageStructValue::ItemAmount(_) |
+      FINLuaProcessorStateStorageStructValue::FINGPUT1Buffer(_) => {},
+    }
+    path.pop();
+  }
+  path.pop();
+}
+
+/// Mutable counterpart to `Visitor`. References stored as separate level name
+/// and path name strings are handed to `visit_object_reference_mut` as a
+/// temporary `ObjectReference` and written back afterwards, so rewriting
+/// references only requires overriding that one method
+pub trait VisitorMut {
+  fn visit_save_mut(&mut self, save: &mut Save, path: &mut VisitPath) {
+    walk_save_mut(self, save, path)
+  }
+
+  fn visit_level_mut(&mut self, level: &mut Level, path: &mut VisitPath) {
+    walk_level_mut(self, level, path)
+  }
+
+  fn visit_object_header_mut(&mut self, _object_header: &mut ObjectHeader, _path: &mut VisitPath) {}
+
+  fn visit_object_mut(&mut self, object: &mut Object, path: &mut VisitPath) {
+    walk_object_mut(self, object, path)
+  }
+
+  fn visit_object_extra_mut(&mut self, extra: &mut ObjectExtra, path: &mut VisitPath) {
+    walk_object_extra_mut(self, extra, path)
+  }
+
+  fn visit_property_mut(&mut self, property: &mut Property, path: &mut VisitPath) {
+    walk_property_mut(self, property, path)
+  }
+
+  fn visit_property_value_mut(&mut self, value: &mut PropertyValue, path: &mut VisitPath) {
+    walk_property_value_mut(self, value, path)
+  }
+
+  fn visit_array_value_mut(&mut self, value: &mut ArrayPropertyValue, path: &mut VisitPath) {
+    walk_array_value_mut(self, value, path)
+  }
+
+  fn visit_array_struct_value_mut(&mut self, value: &mut ArrayPropertyStructValue, path: &mut VisitPath) {
+    walk_array_struct_value_mut(self, value, path)
+  }
+
+  fn visit_map_key_mut(&mut self, key: &mut MapPropertyKey, path: &mut VisitPath) {
+    walk_map_key_mut(self, key, path)
+  }
+
+  fn visit_map_value_mut(&mut self, value: &mut MapPropertyValue, path: &mut VisitPath) {
+    walk_map_value_mut(self, value, path)
+  }
+
+  fn visit_set_value_mut(&mut self, value: &mut SetPropertyValue, path: &mut VisitPath) {
+    walk_set_value_mut(self, value, path)
+  }
+
+  fn visit_struct_value_mut(&mut self, value: &mut StructPropertyValue, path: &mut VisitPath) {
+    walk_struct_value_mut(self, value, path)
+  }
+
+  fn visit_text_mut(&mut self, text: &mut TextProperty, path: &mut VisitPath) {
+    walk_text_mut(self, text, path)
+  }
+
+  fn visit_fin_network_trace_mut(&mut self, trace: &mut FINNetworkTrace, path: &mut VisitPath) {
+    walk_fin_network_trace_mut(self, trace, path)
+  }
+
+  fn visit_fin_lua_processor_state_storage_mut(&mut self, storage: &mut FINLuaProcessorStateStorage, path: &mut VisitPath) {
+    walk_fin_lua_processor_state_storage_mut(self, storage, path)
+  }
+
+  fn visit_object_reference_mut(&mut self, _reference: &mut ObjectReference, _path: &mut VisitPath) {}
+
+  fn visit_string_mut(&mut self, _value: &mut String, _path: &mut VisitPath) {}
+}
+
+/// Hands a level name and path name pair to the visitor as a temporary
+/// `ObjectReference` and moves the (possibly rewritten) values back. These
+/// pairs are saved as they are, so their level name is never left out
+fn visit_reference_pair_mut<V: VisitorMut + ?Sized>(visitor: &mut V, level_name: &mut String, path_name: &mut String, path: &mut VisitPath) {
+  let mut reference = ObjectReference {
+    level_name: mem::take(level_name),
+    path_name: mem::take(path_name),
+    level_is_map: false,
+  };
+  visitor.visit_object_reference_mut(&mut reference, path);
+  *level_name = reference.level_name;
+  *path_name = reference.path_name;
+}
+
+pub fn walk_save_mut<V: VisitorMut + ?Sized>(visitor: &mut V, save: &mut Save, path: &mut VisitPath) {
+  for level in &mut save.levels {
+    path.push(PathSegment::Level(level.name.clone()));
+    visitor.visit_level_mut(level, path);
+    path.pop();
+  }
+}
+
+pub fn walk_level_mut<V: VisitorMut + ?Sized>(visitor: &mut V, level: &mut Level, path: &mut VisitPath) {
+  // Names are captured up front since visiting a header may rename it, and
+  // the object should still be reported under the name it was read with
+  let names: Vec<String> = level.object_headers.iter().map(|h| h.get_instance_name().clone()).collect();
+
+  for (i, object_header) in level.object_headers.iter_mut().enumerate() {
+    path.push(PathSegment::Object(names[i].clone()));
+    visitor.visit_object_header_mut(object_header, path);
+    path.pop();
+  }
+
+  path.push(PathSegment::Field("collectables"));
+  for (i, collectable) in level.collectables.iter_mut().enumerate() {
+    path.push(PathSegment::Index(i));
+    visitor.visit_object_reference_mut(collectable, path);
+    path.pop();
+  }
+  path.pop();
+
+  path.push(PathSegment::Field("second_collectables"));
+  for (i, collectable) in level.second_collectables.iter_mut().enumerate() {
+    path.push(PathSegment::Index(i));
+    visitor.visit_object_reference_mut(collectable, path);
+    path.pop();
+  }
+  path.pop();
+
+  for (i, object) in level.objects.iter_mut().enumerate() {
+    let name = match names.get(i) {
+      Some(n) => n.clone(),
+      None => i.to_string(),
+    };
+    path.push(PathSegment::Object(name));
+    visitor.visit_object_mut(object, path);
+    path.pop();
+  }
+}
+
+pub fn walk_object_mut<V: VisitorMut + ?Sized>(visitor: &mut V, object: &mut Object, path: &mut VisitPath) {
+  let (properties, extra) = match object {
+    Object::Actor(a) => {
+      path.push(PathSegment::Field("parent"));
+      let mut parent = ObjectReference {
+        level_name: mem::take(&mut a.parent_object_root),
+        path_name: mem::take(&mut a.parent_object_name),
+        level_is_map: a.parent_object_root_is_map,
+      };
+      visitor.visit_object_reference_mut(&mut parent, path);
+      a.parent_object_root = parent.level_name;
+      a.parent_object_name = parent.path_name;
+      a.parent_object_root_is_map = parent.level_is_map;
+      path.pop();
+
+      path.push(PathSegment::Field("components"));
+      for (i, component) in a.components.iter_mut().enumerate() {
+        path.push(PathSegment::Index(i));
+        visitor.visit_object_reference_mut(component, path);
+        path.pop();
+      }
+      path.pop();
+
+      (&mut a.properties, &mut a.extra)
+    },
+    Object::Component(c) => (&mut c.properties, &mut c.extra),
+  };
+
+  for property in properties {
+    visitor.visit_property_mut(property, path);
+  }
+
+  if let Some(extra) = extra {
+    path.push(PathSegment::Field("extra"));
+    visitor.visit_object_extra_mut(extra, path);
+    path.pop();
+  }
+}
+
+pub fn walk_object_extra_mut<V: VisitorMut + ?Sized>(visitor: &mut V, extra: &mut ObjectExtra, path: &mut VisitPath) {
+  match extra {
+    ObjectExtra::Circuit(e) => {
+      for (i, circuit) in e.elements.iter_mut().enumerate() {
+        path.push(PathSegment::Index(i));
+        visit_reference_pair_mut(visitor, &mut circuit.level_name, &mut circuit.path_name, path);
+        path.pop();
+      }
+    },
+    ObjectExtra::DroneTransport(e) => {
+      for (field, actions) in [("active_action", &mut e.active_action), ("action_queue", &mut e.action_queue)] {
+        path.push(PathSegment::Field(field));
+        for (i, action) in actions.iter_mut().enumerate() {
+          path.push(PathSegment::Index(i));
+          for property in &mut action.properties {
+            visitor.visit_property_mut(property, path);
+          }
+          path.pop();
+        }
+        path.pop();
+      }
+    },
+    ObjectExtra::Game(e) => {
+      for (i, reference) in e.elements.iter_mut().enumerate() {
+        path.push(PathSegment::Index(i));
+        visitor.visit_object_reference_mut(reference, path);
+        path.pop();
+      }
+    },
+    ObjectExtra::LightweightBuildables(e) => {
+      path.push(PathSegment::Field("classes"));
+      for (i, class) in e.classes.iter_mut().enumerate() {
+        path.push(PathSegment::Index(i));
+        path.push(PathSegment::Field("class"));
+        visitor.visit_object_reference_mut(&mut class.class, path);
+        path.pop();
+        path.push(PathSegment::Field("instances"));
+        for (j, instance) in class.instances.iter_mut().enumerate() {
+          path.push(PathSegment::Index(j));
+          let customization = &mut instance.customization;
+          for (field, reference) in [
+            ("swatch", &mut customization.swatch),
+            ("material", &mut customization.material),
+            ("pattern", &mut customization.pattern),
+            ("skin", &mut customization.skin),
+            ("paint_finish", &mut customization.paint_finish),
+          ] {
+            path.push(PathSegment::Field(field));
+            visitor.visit_object_reference_mut(reference, path);
+            path.pop();
+          }
+          path.push(PathSegment::Field("built_with_recipe"));
+          visitor.visit_object_reference_mut(&mut instance.built_with_recipe, path);
+          path.pop();
+          path.push(PathSegment::Field("blueprint_proxy"));
+          visitor.visit_object_reference_mut(&mut instance.blueprint_proxy, path);
+          path.pop();
+          path.pop();
+        }
+        path.pop();
+        path.pop();
+      }
+      path.pop();
+    },
+    ObjectExtra::Locomotive(e) => {
+      path.push(PathSegment::Field("prev"));
+      visitor.visit_object_reference_mut(&mut e.prev, path);
+      path.pop();
+      path.push(PathSegment::Field("next"));
+      visitor.visit_object_reference_mut(&mut e.next, path);
+      path.pop();
+    },
+    ObjectExtra::PowerLine(e) => {
+      path.push(PathSegment::Field("source"));
+      visitor.visit_object_reference_mut(&mut e.source, path);
+      path.pop();
+      path.push(PathSegment::Field("target"));
+      visitor.visit_object_reference_mut(&mut e.target, path);
+      path.pop();
+    },
+    ObjectExtra::Conveyor(_) | ObjectExtra::PlayerState(_) | ObjectExtra::Vehicle(_) | ObjectExtra::Raw(_) => {},
+  }
+}
+
+pub fn walk_property_mut<V: VisitorMut + ?Sized>(visitor: &mut V, property: &mut Property, path: &mut VisitPath) {
+  path.push(PathSegment::Property(property.name.clone(), property.index));
+  visitor.visit_property_value_mut(&mut property.value, path);
+  path.pop();
+}
+
+pub fn walk_property_value_mut<V: VisitorMut + ?Sized>(visitor: &mut V, value: &mut PropertyValue, path: &mut VisitPath) {
+  match value {
+    PropertyValue::String(s) => visitor.visit_string_mut(s, path),
+    PropertyValue::Object(o) => visitor.visit_object_reference_mut(o, path),
+    PropertyValue::Byte(b) => {
+      if let Some(s) = &mut b.string_value {
+        visitor.visit_string_mut(s, path);
+      }
+    },
+    PropertyValue::Text(t) => visitor.visit_text_mut(t, path),
+    PropertyValue::Array(a) => {
+      for (i, element) in a.elements.iter_mut().enumerate() {
+        path.push(PathSegment::Index(i));
+        visitor.visit_array_value_mut(element, path);
+        path.pop();
+      }
+    },
+    PropertyValue::Map(m) => {
+      for (i, key) in m.keys.iter_mut().enumerate() {
+        path.push(PathSegment::MapKey(i));
+        visitor.visit_map_key_mut(key, path);
+        path.pop();
+      }
+      for (i, value) in m.values.iter_mut().enumerate() {
+        path.push(PathSegment::MapValue(i));
+        visitor.visit_map_value_mut(value, path);
+        path.pop();
+      }
+    },
+    PropertyValue::Set(s) => {
+      for (i, value) in s.values.iter_mut().enumerate() {
+        path.push(PathSegment::Index(i));
+        visitor.visit_set_value_mut(value, path);
+        path.pop();
+      }
+    },
+    PropertyValue::Struct((_, s)) => visitor.visit_struct_value_mut(s, path),
+    PropertyValue::Bool(_) | PropertyValue::Int8(_) | PropertyValue::Int(_) |
+    PropertyValue::UInt32(_) | PropertyValue::Int64(_) | PropertyValue::UInt64(_) |
+    PropertyValue::Float(_) | PropertyValue::Double(_) | PropertyValue::Enum(_) => {},
+  }
+}
+
+pub fn walk_array_value_mut<V: VisitorMut + ?Sized>(visitor: &mut V, value: &mut ArrayPropertyValue, path: &mut VisitPath) {
+  match value {
+    ArrayPropertyValue::Enum(s) | ArrayPropertyValue::Str(s) => visitor.visit_string_mut(s, path),
+    ArrayPropertyValue::Text(t) => visitor.visit_text_mut(t, path),
+    ArrayPropertyValue::Object(o) => visitor.visit_object_reference_mut(o, path),
+    ArrayPropertyValue::Struct(s) => visitor.visit_array_struct_value_mut(s, path),
+    ArrayPropertyValue::Byte(_) | ArrayPropertyValue::Bytes(_) | ArrayPropertyValue::Bool(_) | ArrayPropertyValue::Int(_) |
+    ArrayPropertyValue::Long(_) | ArrayPropertyValue::Float(_) | ArrayPropertyValue::SoftObject(_) => {},
+  }
+}
+
+pub fn walk_array_struct_value_mut<V: VisitorMut + ?Sized>(visitor: &mut V, value: &mut ArrayPropertyStructValue, path: &mut VisitPath) {
+  match value {
+    ArrayPropertyStructValue::InventoryItem(item) => {
+      visitor.visit_string_mut(&mut item.item_name, path);
+      visit_reference_pair_mut(visitor, &mut item.level_name, &mut item.path_name, path);
+    },
+    ArrayPropertyStructValue::FINNetworkTrace(t) => visitor.visit_fin_network_trace_mut(t, path),
+    ArrayPropertyStructValue::Properties(properties) => {
+      for property in properties {
+        visitor.visit_property_mut(property, path);
+      }
+    },
+    ArrayPropertyStructValue::GUID(_) | ArrayPropertyStructValue::Vector(_) |
+    ArrayPropertyStructValue::LinearColor(_) | ArrayPropertyStructValue::FINGPUT1BufferPixel(_) |
+    ArrayPropertyStructValue::None => {},
+  }
+}
+
+pub fn walk_map_key_mut<V: VisitorMut + ?Sized>(visitor: &mut V, key: &mut MapPropertyKey, path: &mut VisitPath) {
+  match key {
+    MapPropertyKey::String(s) => visitor.visit_string_mut(s, path),
+    MapPropertyKey::Object(o) => visitor.visit_object_reference_mut(o, path),
+    MapPropertyKey::Properties(properties) => {
+      for property in properties {
+        visitor.visit_property_mut(property, path);
+      }
+    },
+    MapPropertyKey::Int(_) | MapPropertyKey::Long(_) | MapPropertyKey::IntVector(_) |
+    MapPropertyKey::FloatVector(_) | MapPropertyKey::DoubleVector(_) => {},
+  }
+}
+
+pub fn walk_map_value_mut<V: VisitorMut + ?Sized>(visitor: &mut V, value: &mut MapPropertyValue, path: &mut VisitPath) {
+  match value {
+    MapPropertyValue::String(s) => visitor.visit_string_mut(s, path),
+    MapPropertyValue::Text(t) => visitor.visit_text_mut(t, path),
+    MapPropertyValue::Object(o) => visitor.visit_object_reference_mut(o, path),
+    MapPropertyValue::Struct(properties) => {
+      for property in properties {
+        visitor.visit_property_mut(property, path);
+      }
+    },
+    MapPropertyValue::Byte(_) | MapPropertyValue::Bool(_) | MapPropertyValue::Int(_) |
+    MapPropertyValue::Long(_) | MapPropertyValue::Float(_) | MapPropertyValue::Double(_) => {},
+  }
+}
+
+pub fn walk_set_value_mut<V: VisitorMut + ?Sized>(visitor: &mut V, value: &mut SetPropertyValue, path: &mut VisitPath) {
+  match value {
+    SetPropertyValue::String(s) => visitor.visit_string_mut(s, path),
+    SetPropertyValue::Object(o) => visitor.visit_object_reference_mut(o, path),
+    SetPropertyValue::FINNetworkTrace(t) => visitor.visit_fin_network_trace_mut(t, path),
+    SetPropertyValue::Int(_) | SetPropertyValue::UInt32(_) | SetPropertyValue::Vector(_) => {},
+  }
+}
+
+pub fn walk_struct_value_mut<V: VisitorMut + ?Sized>(visitor: &mut V, value: &mut StructPropertyValue, path: &mut VisitPath) {
+  match value {
+    StructPropertyValue::RailroadTrackPosition(p) => {
+      path.push(PathSegment::Field("object"));
+      visitor.visit_object_reference_mut(&mut p.object, path);
+      path.pop();
+    },
+    StructPropertyValue::InventoryItem(item) => {
+      path.push(PathSegment::Field("item_name"));
+      visitor.visit_string_mut(&mut item.item_name, path);
+      path.pop();
+      path.push(PathSegment::Field("object"));
+      visitor.visit_object_reference_mut(&mut item.object, path);
+      path.pop();
+      visitor.visit_property_mut(&mut item.property, path);
+    },
+    StructPropertyValue::FINNetworkTrace(t) => visitor.visit_fin_network_trace_mut(t, path),
+    StructPropertyValue::FINLuaProcessorStateStorage(s) => visitor.visit_fin_lua_processor_state_storage_mut(s, path),
+    StructPropertyValue::Properties(properties) => {
+      for property in properties {
+        visitor.visit_property_mut(property, path);
+      }
+    },
+    StructPropertyValue::Color(_) | StructPropertyValue::LinearColor(_) |
+    StructPropertyValue::FloatVector(_) | StructPropertyValue::DoubleVector(_) |
+    StructPropertyValue::IntVector2D(_) | StructPropertyValue::FloatVector2D(_) |
+    StructPropertyValue::DoubleVector2D(_) | StructPropertyValue::IntVector4(_) |
+    StructPropertyValue::DoubleVector4(_) | StructPropertyValue::FloatQuaternion(_) |
+    StructPropertyValue::DoubleQuaternion(_) | StructPropertyValue::Box(_) |
+    StructPropertyValue::TimerHandle(_) | StructPropertyValue::GUID(_) |
+    StructPropertyValue::FluidBox(_) | StructPropertyValue::SlateBrush(_) |
+    StructPropertyValue::DateTime(_) | StructPropertyValue::FICFrameRange(_) |
+    StructPropertyValue::IntPoint(_) | StructPropertyValue::None => {},
+  }
+}
+
+pub fn walk_text_mut<V: VisitorMut + ?Sized>(visitor: &mut V, text: &mut TextProperty, path: &mut VisitPath) {
+  match &mut text.value {
+    TextPropertyHistory::BaseHistory(h) => visitor.visit_string_mut(&mut h.value, path),
+    TextPropertyHistory::ArgumentHistory(h) => {
+      path.push(PathSegment::Field("source_format"));
+      visitor.visit_text_mut(&mut h.source_format, path);
+      path.pop();
+      path.push(PathSegment::Field("arguments"));
+      for (i, argument) in h.arguments.iter_mut().enumerate() {
+        path.push(PathSegment::Index(i));
+        visitor.visit_text_mut(&mut argument.value, path);
+        path.pop();
+      }
+      path.pop();
+    },
+    TextPropertyHistory::TransformHistory(h) => visitor.visit_text_mut(&mut h.source_text, path),
+    TextPropertyHistory::NoneHistory(h) => visitor.visit_string_mut(&mut h.value, path),
+    TextPropertyHistory::StringTableEntryHistory(_) | TextPropertyHistory::None => {},
+  }
+}
+
+pub fn walk_fin_network_trace_mut<V: VisitorMut + ?Sized>(visitor: &mut V, trace: &mut FINNetworkTrace, path: &mut VisitPath) {
+  visit_reference_pair_mut(visitor, &mut trace.level_name, &mut trace.path_name, path);
+
+  if let Some(prev) = &mut trace.prev {
+    path.push(PathSegment::Field("prev"));
+    visitor.visit_fin_network_trace_mut(prev, path);
+    path.pop();
+  }
+}
+
+pub fn walk_fin_lua_processor_state_storage_mut<V: VisitorMut + ?Sized>(visitor: &mut V, storage: &mut FINLuaProcessorStateStorage, path: &mut VisitPath) {
+  path.push(PathSegment::Field("trace"));
+  for (i, trace) in storage.trace.iter_mut().enumerate() {
+    path.push(PathSegment::Index(i));
+    visitor.visit_fin_network_trace_mut(trace, path);
+    path.pop();
+  }
+  path.pop();
+
+  path.push(PathSegment::Field("reference"));
+  for (i, reference) in storage.reference.iter_mut().enumerate() {
+    path.push(PathSegment::Index(i));
+    visitor.visit_object_reference_mut(reference, path);
+    path.pop();
+  }
+  path.pop();
+
+  path.push(PathSegment::Field("structs"));
+  for (i, s) in storage.structs.iter_mut().enumerate() {
+    path.push(PathSegment::Index(i));
+    match &mut s.value {
+      FINLuaProcessorStateStorageStructValue::InventoryStack(stack) => {
+        visitor.visit_struct_value_mut(&mut stack.unk_struct_1.1, path);
+      },
+      FINLuaProcessorStateStorageStructValue::FINTrackGraph(trace, _) => {
+        visitor.visit_fin_network_trace_mut(trace, path);
+      },
+      FINLuaProcessorStateStorageStructValue::Vector(_) |
+      FINLuaProcessorStateStorageStructValue::LinearColor(_) |
+      FINLuaProcessorStateStorageStructValue::ItemAmount(_) |
+      FINLuaProcessorStateStorageStructValue::FINGPUT1Buffer(_) => {},
+    }
+    path.pop();
+  }
+  path.pop();
+}
@@ -0,0 +1,1068 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::errors::ParseError;
+use crate::property::*;
+use crate::save::*;
+use crate::{ReadSaveFileBytes, Result};
+
+/// Player type bytes which are followed by a known layout
+const PLAYER_ID_LAYOUTS: [u8; 8] = [3, 8, 17, 25, 29, 241, 248, 249];
+
+/// The most bytes of the body compressed into a single chunk
+const MAX_CHUNK_SIZE: usize = 131_072;
+
+/// The counterpart to `ReadSaveFileBytes`: extends `byteorder`'s
+/// `WriteBytesExt` with functions that mirror each of the reader's functions
+/// so that a save can be written back out.
+///
+/// Every size field (`Property::size`, `ArrayPropertyStruct::size_bytes` and
+/// the objects' `size_bytes`) is ignored and recomputed from the written bytes
+/// instead, so values can be edited freely beforehand.
+pub trait WriteSaveFileBytes: WriteBytesExt {
+  /// Writes a quaternion with values as 32-bit floats
+  fn write_quaternion<E: ByteOrder>(&mut self, value: &Quaternion<f32>) -> Result<()> {
+    self.write_f32::<E>(value.x)?;
+    self.write_f32::<E>(value.y)?;
+    self.write_f32::<E>(value.z)?;
+    self.write_f32::<E>(value.w)?;
+    Ok(())
+  }
+
+  /// Writes a quaternion with values as 64-bit floats
+  fn write_quaternion_double<E: ByteOrder>(&mut self, value: &Quaternion<f64>) -> Result<()> {
+    self.write_f64::<E>(value.x)?;
+    self.write_f64::<E>(value.y)?;
+    self.write_f64::<E>(value.z)?;
+    self.write_f64::<E>(value.w)?;
+    Ok(())
+  }
+
+  /// Writes a 2D vector with values as 32-bit floats
+  fn write_vector2d<E: ByteOrder>(&mut self, value: &Vector2D<f32>) -> Result<()> {
+    self.write_f32::<E>(value.x)?;
+    self.write_f32::<E>(value.y)?;
+    Ok(())
+  }
+
+  /// Writes a 2D vector with values as 64-bit floats
+  fn write_vector2d_double<E: ByteOrder>(&mut self, value: &Vector2D<f64>) -> Result<()> {
+    self.write_f64::<E>(value.x)?;
+    self.write_f64::<E>(value.y)?;
+    Ok(())
+  }
+
+  /// Writes a 2D vector with values as 32-bit integers
+  fn write_vector2d_int<E: ByteOrder>(&mut self, value: &Vector2D<i32>) -> Result<()> {
+    self.write_i32::<E>(value.x)?;
+    self.write_i32::<E>(value.y)?;
+    Ok(())
+  }
+
+  /// Writes a 3D vector with values as 32-bit floats
+  fn write_vector<E: ByteOrder>(&mut self, value: &Vector<f32>) -> Result<()> {
+    self.write_f32::<E>(value.x)?;
+    self.write_f32::<E>(value.y)?;
+    self.write_f32::<E>(value.z)?;
+    Ok(())
+  }
+
+  /// Writes a 3D vector with values as 64-bit floats
+  fn write_vector_double<E: ByteOrder>(&mut self, value: &Vector<f64>) -> Result<()> {
+    self.write_f64::<E>(value.x)?;
+    self.write_f64::<E>(value.y)?;
+    self.write_f64::<E>(value.z)?;
+    Ok(())
+  }
+
+  /// Writes a 3D vector with values as 32-bit integers
+  fn write_vector_int<E: ByteOrder>(&mut self, value: &Vector<i32>) -> Result<()> {
+    self.write_i32::<E>(value.x)?;
+    self.write_i32::<E>(value.y)?;
+    self.write_i32::<E>(value.z)?;
+    Ok(())
+  }
+
+  /// Writes a 4D vector with values as 64-bit floats
+  fn write_vector4_double<E: ByteOrder>(&mut self, value: &Vector4<f64>) -> Result<()> {
+    self.write_f64::<E>(value.a)?;
+    self.write_f64::<E>(value.b)?;
+    self.write_f64::<E>(value.c)?;
+    self.write_f64::<E>(value.d)?;
+    Ok(())
+  }
+
+  /// Writes a 4D vector with values as 32-bit integers
+  fn write_vector4_int<E: ByteOrder>(&mut self, value: &Vector4<i32>) -> Result<()> {
+    self.write_i32::<E>(value.a)?;
+    self.write_i32::<E>(value.b)?;
+    self.write_i32::<E>(value.c)?;
+    self.write_i32::<E>(value.d)?;
+    Ok(())
+  }
+
+  /// Writes an RGB color with alpha channel with values as 32-bit floats
+  fn write_color<E: ByteOrder>(&mut self, value: &Color<f32>) -> Result<()> {
+    self.write_f32::<E>(value.red)?;
+    self.write_f32::<E>(value.green)?;
+    self.write_f32::<E>(value.blue)?;
+    self.write_f32::<E>(value.alpha)?;
+    Ok(())
+  }
+
+  /// Writes an RGB color with alpha channel with values as bytes
+  fn write_color_byte(&mut self, value: &Color<u8>) -> Result<()> {
+    self.write_u8(value.red)?;
+    self.write_u8(value.green)?;
+    self.write_u8(value.blue)?;
+    self.write_u8(value.alpha)?;
+    Ok(())
+  }
+
+  /// Writes a string as its UTF-16 code units, reversing `read_hex`
+  fn write_hex<E: ByteOrder>(&mut self, value: &str) -> Result<()> {
+    for unit in value.encode_utf16() {
+      self.write_u16::<E>(unit)?;
+    }
+    Ok(())
+  }
+
+  /// Writes a string prefixed by its length including the null termination
+  /// byte. ASCII strings are written as UTF-8 with a positive length and
+  /// anything else as UTF-16 with a negative length
+  fn write_length_prefixed_string<E: ByteOrder>(&mut self, value: &str) -> Result<()> {
+    if value.is_empty() {
+      self.write_i32::<E>(0)?;
+    } else if value.is_ascii() {
+      self.write_i32::<E>(value.len() as i32 + 1)?;
+      self.write_all(value.as_bytes())?;
+      self.write_u8(0)?;
+    } else {
+      let units: Vec<u16> = value.encode_utf16().collect();
+      self.write_i32::<E>(-(units.len() as i32 + 1))?;
+      for unit in units {
+        self.write_u16::<E>(unit)?;
+      }
+      self.write_u16::<E>(0)?;
+    }
+    Ok(())
+  }
+
+  /// Writes the file header
+  fn write_header<E: ByteOrder>(&mut self, header: &Header) -> Result<()> {
+    self.write_i32::<E>(header.save_header_version)?;
+    self.write_i32::<E>(header.save_file_version)?;
+    self.write_i32::<E>(header.build_version)?;
+    self.write_length_prefixed_string::<E>(&header.map_name)?;
+    self.write_length_prefixed_string::<E>(&header.map_options)?;
+    self.write_length_prefixed_string::<E>(&header.session_name)?;
+    self.write_i32::<E>(header.played_seconds)?;
+    self.write_i64::<E>(header.save_timestamp)?;
+    self.write_i8(header.session_visibility)?;
+    self.write_i32::<E>(header.editor_object_version)?;
+    self.write_length_prefixed_string::<E>(&header.mod_metadata)?;
+    self.write_i32::<E>(header.mod_flags)?;
+    self.write_length_prefixed_string::<E>(&header.save_identifier)?;
+    self.write_i32::<E>(header.is_partitioned_world)?;
+    self.write_hex::<E>(&header.saved_data_hash)?;
+    self.write_i32::<E>(header.is_creative_mode_enabled)?;
+    Ok(())
+  }
+
+  /// Compresses part of the body and writes it preceded by its chunk header
+  fn write_chunk<E: ByteOrder>(&mut self, bytes: &[u8]) -> Result<()> {
+    let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+    encoder.write_all(bytes)?;
+    let chunk_bytes = encoder.finish()?;
+
+    // The Unreal Engine package signature and archive header version
+    self.write_u32::<E>(0x9E2A83C1)?;
+    self.write_u32::<E>(0x22222222)?;
+    self.write_u64::<E>(MAX_CHUNK_SIZE as u64)?;
+
+    // The compression algorithm, i.e. zlib
+    self.write_u8(3)?;
+
+    // The compressed and uncompressed sizes, which are repeated
+    for _ in 0..2 {
+      self.write_u64::<E>(chunk_bytes.len() as u64)?;
+      self.write_u64::<E>(bytes.len() as u64)?;
+    }
+
+    self.write_all(&chunk_bytes)?;
+    Ok(())
+  }
+
+  /// Writes the partition objects which start the main body
+  fn write_partitions<E: ByteOrder>(&mut self, partitions: &Partitions) -> Result<()> {
+    self.write_i32::<E>(partitions.partitions.len() as i32)?;
+    for partition in &partitions.partitions {
+      self.write_length_prefixed_string::<E>(&partition.name)?;
+      self.write_i32::<E>(partition.cell_size)?;
+      self.write_u32::<E>(partition.hash)?;
+      self.write_i32::<E>(partition.levels.len() as i32)?;
+      for (level_key, level_value) in &partition.levels {
+        self.write_length_prefixed_string::<E>(level_key)?;
+        self.write_u32::<E>(*level_value)?;
+      }
+    }
+    Ok(())
+  }
+
+  /// Writes a reference, putting back the map name as its level name if the
+  /// reader left it out
+  fn write_object_reference<E: ByteOrder>(&mut self, reference: &ObjectReference, map_name: &str) -> Result<()> {
+    if reference.level_is_map && reference.level_name.is_empty() {
+      self.write_length_prefixed_string::<E>(map_name)?;
+    } else {
+      self.write_length_prefixed_string::<E>(&reference.level_name)?;
+    }
+    self.write_length_prefixed_string::<E>(&reference.path_name)?;
+    Ok(())
+  }
+
+  /// Writes a level name and path name exactly as they are
+  fn write_object_reference_raw<E: ByteOrder>(&mut self, reference: &ObjectReference) -> Result<()> {
+    self.write_length_prefixed_string::<E>(&reference.level_name)?;
+    self.write_length_prefixed_string::<E>(&reference.path_name)?;
+    Ok(())
+  }
+
+  /// Writes an object header, prefixed by its object type
+  fn write_level_object_header<E: ByteOrder>(&mut self, object_header: &ObjectHeader, map_name: &str) -> Result<()> {
+    match object_header {
+      ObjectHeader::Component(c) => {
+        self.write_i32::<E>(0)?;
+        self.write_length_prefixed_string::<E>(&c.type_path)?;
+        self.write_length_prefixed_string::<E>(c.root_object.as_deref().unwrap_or(map_name))?;
+        self.write_length_prefixed_string::<E>(&c.instance_name)?;
+        self.write_length_prefixed_string::<E>(&c.parent_actor_name)?;
+      },
+      ObjectHeader::Actor(a) => {
+        self.write_i32::<E>(1)?;
+        self.write_length_prefixed_string::<E>(&a.type_path)?;
+        self.write_length_prefixed_string::<E>(a.root_object.as_deref().unwrap_or(map_name))?;
+        self.write_length_prefixed_string::<E>(&a.instance_name)?;
+        self.write_i32::<E>(a.needs_transform)?;
+        self.write_quaternion::<E>(&a.rotation)?;
+        self.write_vector::<E>(&a.position)?;
+        self.write_vector::<E>(&a.scale)?;
+        self.write_i32::<E>(a.was_placed_in_level)?;
+      },
+    }
+    Ok(())
+  }
+
+  /// Writes a byte flag followed by the GUID, if any
+  fn write_property_guid<E: ByteOrder>(&mut self, guid: &Option<String>) -> Result<()> {
+    match guid {
+      Some(g) => {
+        self.write_u8(1)?;
+        self.write_hex::<E>(g)?;
+      },
+      None => self.write_u8(0)?,
+    }
+    Ok(())
+  }
+
+  /// Writes the 17 bytes following a struct property's type, or zeroes if
+  /// there are none
+  fn write_struct_guid(&mut self, guid: &Option<Vec<u8>>) -> Result<()> {
+    match guid {
+      Some(g) if g.len() == 17 => self.write_all(g)?,
+      _ => self.write_all(&[0; 17])?,
+    }
+    Ok(())
+  }
+
+  /// Writes a [FicsIt-Network Network trace](https://docs.ficsit.app/ficsit-networks/latest/NetworkTrace.html)
+  fn write_fin_network_trace<E: ByteOrder>(&mut self, trace: &FINNetworkTrace) -> Result<()> {
+    self.write_length_prefixed_string::<E>(&trace.level_name)?;
+    self.write_length_prefixed_string::<E>(&trace.path_name)?;
+
+    match &trace.prev {
+      Some(prev) => {
+        self.write_i32::<E>(1)?;
+        self.write_fin_network_trace::<E>(prev)?;
+      },
+      None => self.write_i32::<E>(0)?,
+    }
+
+    match &trace.step {
+      Some(step) => {
+        self.write_i32::<E>(1)?;
+        self.write_length_prefixed_string::<E>(step)?;
+      },
+      None => self.write_i32::<E>(0)?,
+    }
+
+    Ok(())
+  }
+
+  /// Writes a [FicsIt-Network GPUT buffer pixel](https://github.com/Panakotta00/FicsIt-Networks/blob/master/Source/FicsItNetworks/Public/Computer/FINComputerGPUT1.h)
+  fn write_fingput1_buffer_pixel<E: ByteOrder>(&mut self, pixel: &FINGPUT1BufferPixel) -> Result<()> {
+    self.write_hex::<E>(&pixel.character)?;
+    self.write_color::<E>(&pixel.foreground_color)?;
+    self.write_color::<E>(&pixel.background_color)?;
+    Ok(())
+  }
+
+  /// Writes a [FicsIt-Network Lua processor state storage](https://github.com/Panakotta00/FicsIt-Networks/blob/master/Source/FicsItNetworksLua/Private/FINLuaProcessorStateStorage.cpp)
+  fn write_fin_lua_processor_state_storage<E: ByteOrder>(&mut self, data: &FINLuaProcessorStateStorage, header: &Header) -> Result<()> {
+    self.write_i32::<E>(data.trace.len() as i32)?;
+    for trace in &data.trace {
+      self.write_fin_network_trace::<E>(trace)?;
+    }
+
+    self.write_i32::<E>(data.reference.len() as i32)?;
+    for reference in &data.reference {
+      self.write_object_reference::<E>(reference, &header.map_name)?;
+    }
+
+    self.write_length_prefixed_string::<E>(&data.thread)?;
+    self.write_length_prefixed_string::<E>(&data.globals)?;
+
+    self.write_i32::<E>(data.structs.len() as i32)?;
+    for s in &data.structs {
+      self.write_i32::<E>(s.unk_int_1)?;
+      self.write_length_prefixed_string::<E>(&s.class_name)?;
+
+      match &s.value {
+        FINLuaProcessorStateStorageStructValue::Vector(v) => self.write_vector::<E>(v)?,
+        FINLuaProcessorStateStorageStructValue::LinearColor(c) => self.write_color::<E>(c)?,
+        FINLuaProcessorStateStorageStructValue::InventoryStack(stack) => {
+          self.write_length_prefixed_string::<E>(&stack.unk_str_1)?;
+          self.write_length_prefixed_string::<E>(&stack.unk_str_2)?;
+          self.write_i32::<E>(stack.unk_int_1)?;
+          self.write_i32::<E>(stack.unk_int_2)?;
+          self.write_length_prefixed_string::<E>(&stack.unk_struct_1.0)?;
+          self.write_struct_guid(&stack.unk_struct_1_guid)?;
+          self.write_struct_property_value::<E>(&stack.unk_struct_1.1, header)?;
+          self.write_length_prefixed_string::<E>(&stack.unk_str_3)?;
+        },
+        FINLuaProcessorStateStorageStructValue::ItemAmount(amount) => {
+          self.write_i32::<E>(amount.unk_int_1)?;
+          self.write_length_prefixed_string::<E>(&amount.unk_str_1)?;
+          self.write_i32::<E>(amount.unk_int_2)?;
+        },
+        FINLuaProcessorStateStorageStructValue::FINTrackGraph(trace, id) => {
+          self.write_fin_network_trace::<E>(trace)?;
+          self.write_i32::<E>(*id)?;
+        },
+        FINLuaProcessorStateStorageStructValue::FINGPUT1Buffer(buffer) => {
+          self.write_i32::<E>(buffer.x)?;
+          self.write_i32::<E>(buffer.y)?;
+          self.write_i32::<E>(buffer.buffer.len() as i32)?;
+          self.write_length_prefixed_string::<E>(&buffer.name)?;
+          self.write_length_prefixed_string::<E>(&buffer.r#type)?;
+          self.write_i32::<E>(buffer.length)?;
+          for pixel in &buffer.buffer {
+            self.write_fingput1_buffer_pixel::<E>(pixel)?;
+          }
+          self.write_hex::<E>(&buffer.unk_str_1)?;
+        },
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Writes the elements of an array property whose element is of type
+  /// struct, preceded by the struct's meta
+  fn write_array_property_struct<E: ByteOrder>(&mut self, property_name: &str, property: &ArrayProperty, header: &Header) -> Result<()> {
+    let struct_meta = property.struct_meta.clone().unwrap_or_default();
+
+    let mut elements: Vec<u8> = vec![];
+    for element in &property.elements {
+      let ArrayPropertyValue::Struct(value) = element else {
+        continue;
+      };
+      match value {
+        ArrayPropertyStructValue::InventoryItem(item) => {
+          elements.write_i32::<E>(item.unk_int_1)?;
+          elements.write_length_prefixed_string::<E>(&item.item_name)?;
+          elements.write_length_prefixed_string::<E>(&item.level_name)?;
+          elements.write_length_prefixed_string::<E>(&item.path_name)?;
+        },
+        ArrayPropertyStructValue::GUID(g) => elements.write_hex::<E>(g)?,
+        ArrayPropertyStructValue::FINNetworkTrace(t) => elements.write_fin_network_trace::<E>(t)?,
+        ArrayPropertyStructValue::Vector(v) => elements.write_vector_double::<E>(v)?,
+        ArrayPropertyStructValue::LinearColor(c) => elements.write_color::<E>(c)?,
+        ArrayPropertyStructValue::FINGPUT1BufferPixel(p) => elements.write_fingput1_buffer_pixel::<E>(p)?,
+        ArrayPropertyStructValue::Properties(properties) => {
+          elements.write_properties::<E>(properties, header)?;
+        },
+        ArrayPropertyStructValue::None => {},
+      }
+    }
+
+    self.write_length_prefixed_string::<E>(property_name)?;
+    self.write_length_prefixed_string::<E>("StructProperty")?;
+    self.write_i32::<E>(elements.len() as i32)?;
+    self.write_i32::<E>(0)?;
+    self.write_length_prefixed_string::<E>(&struct_meta.r#type)?;
+    self.write_i32::<E>(struct_meta.guid1)?;
+    self.write_i32::<E>(struct_meta.guid2)?;
+    self.write_i32::<E>(struct_meta.guid3)?;
+    self.write_i32::<E>(struct_meta.guid4)?;
+    self.write_u8(0)?;
+    self.write_all(&elements)?;
+
+    Ok(())
+  }
+
+  /// Writes the value of an array property, i.e. everything after its element
+  /// type
+  fn write_array_property_value<E: ByteOrder>(&mut self, property_name: &str, property: &ArrayProperty, header: &Header) -> Result<()> {
+    self.write_i32::<E>(property.num_elements() as i32)?;
+
+    if property.r#type == "Struct" {
+      return self.write_array_property_struct::<E>(property_name, property, header);
+    }
+
+    for element in &property.elements {
+      match element {
+        ArrayPropertyValue::Byte(b) => self.write_u8(*b)?,
+        ArrayPropertyValue::Bytes(b) => self.write_all(b)?,
+        ArrayPropertyValue::Bool(b) => self.write_u8(*b)?,
+        ArrayPropertyValue::Int(i) => self.write_i32::<E>(*i)?,
+        ArrayPropertyValue::Long(l) => self.write_i64::<E>(*l)?,
+        ArrayPropertyValue::Float(f) => self.write_f32::<E>(*f)?,
+        ArrayPropertyValue::Enum(s) | ArrayPropertyValue::Str(s) => self.write_length_prefixed_string::<E>(s)?,
+        ArrayPropertyValue::Text(t) => self.write_text_property::<E>(t)?,
+        ArrayPropertyValue::Object(o) => self.write_object_reference::<E>(o, &header.map_name)?,
+        ArrayPropertyValue::SoftObject(o) => {
+          self.write_length_prefixed_string::<E>(&o.package_name)?;
+          self.write_length_prefixed_string::<E>(&o.asset_name)?;
+          self.write_length_prefixed_string::<E>(&o.sub_path)?;
+        },
+        ArrayPropertyValue::Struct(_) => {},
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Writes a key of a map property
+  fn write_map_property_key<E: ByteOrder>(&mut self, key: &MapPropertyKey, header: &Header) -> Result<()> {
+    match key {
+      MapPropertyKey::Int(i) => self.write_i32::<E>(*i)?,
+      MapPropertyKey::Long(l) => self.write_i64::<E>(*l)?,
+      MapPropertyKey::String(s) => self.write_length_prefixed_string::<E>(s)?,
+      MapPropertyKey::Object(o) => self.write_object_reference::<E>(o, &header.map_name)?,
+      MapPropertyKey::IntVector(v) => self.write_vector_int::<E>(v)?,
+      MapPropertyKey::FloatVector(v) => self.write_vector::<E>(v)?,
+      MapPropertyKey::DoubleVector(v) => self.write_vector_double::<E>(v)?,
+      MapPropertyKey::Properties(properties) => self.write_properties::<E>(properties, header)?,
+    }
+    Ok(())
+  }
+
+  /// Writes the value of a map property, i.e. everything after its key and
+  /// value types
+  fn write_map_property_value<E: ByteOrder>(&mut self, property: &MapProperty, header: &Header) -> Result<()> {
+    self.write_i32::<E>(property.mode_type)?;
+    if property.mode_type == 2 {
+      self.write_length_prefixed_string::<E>(property.unk_mode_2.as_deref().unwrap_or_default())?;
+      self.write_length_prefixed_string::<E>(property.unk_mode_3.as_deref().unwrap_or_default())?;
+    } else if property.mode_type == 3 {
+      self.write_hex::<E>(property.unk_mode_1.as_deref().unwrap_or_default())?;
+      self.write_length_prefixed_string::<E>(property.unk_mode_2.as_deref().unwrap_or_default())?;
+      self.write_length_prefixed_string::<E>(property.unk_mode_3.as_deref().unwrap_or_default())?;
+    }
+
+    self.write_i32::<E>(property.keys.len() as i32)?;
+    for (key, value) in property.keys.iter().zip(&property.values) {
+      self.write_map_property_key::<E>(key, header)?;
+
+      match value {
+        MapPropertyValue::Byte(b) | MapPropertyValue::Bool(b) => self.write_u8(*b)?,
+        MapPropertyValue::String(s) => {
+          if property.value_type == "Str" {
+            for unk in [&property.unk_float_1, &property.unk_float_2, &property.unk_float_3] {
+              self.write_numeric_f32::<E>(unk)?;
+            }
+          }
+          self.write_length_prefixed_string::<E>(s)?;
+        },
+        MapPropertyValue::Int(i) => self.write_i32::<E>(*i)?,
+        MapPropertyValue::Long(l) => self.write_i64::<E>(*l)?,
+        MapPropertyValue::Float(f) => self.write_f32::<E>(*f)?,
+        MapPropertyValue::Double(d) => self.write_f64::<E>(*d)?,
+        MapPropertyValue::Text(t) => self.write_text_property::<E>(t)?,
+        MapPropertyValue::Object(o) => self.write_object_reference::<E>(o, &header.map_name)?,
+        MapPropertyValue::Struct(properties) => self.write_properties::<E>(properties, header)?,
+      }
+    }
+
+    // A key without a value is one where the reader stopped early to read a
+    // mod's data (`BGU_Subsystem`, `LBBalancerData` or `Sub_SR`)
+    if let Some(key) = property.keys.get(property.values.len()) {
+      self.write_map_property_key::<E>(key, header)?;
+
+      if property.value_type == "Object" {
+        for unk in [&property.unk_float_1, &property.unk_float_2, &property.unk_float_3, &property.unk_float_4] {
+          self.write_numeric_f32::<E>(unk)?;
+        }
+        self.write_length_prefixed_string::<E>(property.unk_str_1.as_deref().unwrap_or_default())?;
+      } else if property.m_normal_index.is_some() {
+        for index in [property.m_normal_index, property.m_overflow_index, property.m_filter_index] {
+          self.write_i32::<E>(index.unwrap_or_default())?;
+        }
+      } else {
+        for unk in [&property.unk_float_1, &property.unk_float_2, &property.unk_float_3] {
+          self.write_numeric_f64::<E>(unk)?;
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Writes an optional `Numeric` as a 32-bit float, defaulting to zero
+  fn write_numeric_f32<E: ByteOrder>(&mut self, value: &Option<Numeric>) -> Result<()> {
+    let value = match value {
+      Some(Numeric::Int(i)) => *i as f32,
+      Some(Numeric::Long(l)) => *l as f32,
+      Some(Numeric::Float(f)) => *f,
+      Some(Numeric::Double(d)) => *d as f32,
+      None => 0.0,
+    };
+    self.write_f32::<E>(value)?;
+    Ok(())
+  }
+
+  /// Writes an optional `Numeric` as a 64-bit float, defaulting to zero
+  fn write_numeric_f64<E: ByteOrder>(&mut self, value: &Option<Numeric>) -> Result<()> {
+    let value = match value {
+      Some(Numeric::Int(i)) => *i as f64,
+      Some(Numeric::Long(l)) => *l as f64,
+      Some(Numeric::Float(f)) => *f as f64,
+      Some(Numeric::Double(d)) => *d,
+      None => 0.0,
+    };
+    self.write_f64::<E>(value)?;
+    Ok(())
+  }
+
+  /// Writes the value of a set property, i.e. everything after its element
+  /// type and GUID flag
+  fn write_set_property_value<E: ByteOrder>(&mut self, property: &SetProperty, header: &Header) -> Result<()> {
+    // Number of elements to remove
+    self.write_i32::<E>(0)?;
+
+    self.write_i32::<E>(property.values.len() as i32)?;
+    for value in &property.values {
+      match value {
+        SetPropertyValue::Int(i) => self.write_i32::<E>(*i)?,
+        SetPropertyValue::UInt32(u) => self.write_u32::<E>(*u)?,
+        SetPropertyValue::Object(o) => self.write_object_reference::<E>(o, &header.map_name)?,
+        SetPropertyValue::String(s) => self.write_length_prefixed_string::<E>(s)?,
+        SetPropertyValue::Vector(v) => self.write_vector::<E>(v)?,
+        SetPropertyValue::FINNetworkTrace(t) => self.write_fin_network_trace::<E>(t)?,
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Writes the value of a struct property, i.e. everything after its struct
+  /// type and GUID
+  fn write_struct_property_value<E: ByteOrder>(&mut self, value: &StructPropertyValue, header: &Header) -> Result<()> {
+    match value {
+      StructPropertyValue::Color(c) => self.write_color_byte(c)?,
+      StructPropertyValue::LinearColor(c) => self.write_color::<E>(c)?,
+      StructPropertyValue::FloatVector(v) => self.write_vector::<E>(v)?,
+      StructPropertyValue::DoubleVector(v) => self.write_vector_double::<E>(v)?,
+      StructPropertyValue::IntVector2D(v) | StructPropertyValue::IntPoint(v) => self.write_vector2d_int::<E>(v)?,
+      StructPropertyValue::FloatVector2D(v) => self.write_vector2d::<E>(v)?,
+      StructPropertyValue::DoubleVector2D(v) => self.write_vector2d_double::<E>(v)?,
+      StructPropertyValue::IntVector4(v) => self.write_vector4_int::<E>(v)?,
+      StructPropertyValue::DoubleVector4(v) => self.write_vector4_double::<E>(v)?,
+      StructPropertyValue::FloatQuaternion(q) => self.write_quaternion::<E>(q)?,
+      StructPropertyValue::DoubleQuaternion(q) => self.write_quaternion_double::<E>(q)?,
+      StructPropertyValue::Box(b) => {
+        self.write_vector_double::<E>(&b.min)?;
+        self.write_vector_double::<E>(&b.max)?;
+        self.write_u8(b.is_valid)?;
+      },
+      StructPropertyValue::RailroadTrackPosition(p) => {
+        self.write_object_reference::<E>(&p.object, &header.map_name)?;
+        self.write_f32::<E>(p.offset)?;
+        self.write_f32::<E>(p.forward)?;
+      },
+      StructPropertyValue::TimerHandle(s) | StructPropertyValue::SlateBrush(s) => self.write_length_prefixed_string::<E>(s)?,
+      StructPropertyValue::GUID(g) => self.write_hex::<E>(g)?,
+      StructPropertyValue::InventoryItem(item) => {
+        self.write_i32::<E>(item.unk_int_1)?;
+        self.write_length_prefixed_string::<E>(&item.item_name)?;
+        self.write_object_reference::<E>(&item.object, &header.map_name)?;
+        self.write_property::<E>(&item.property, header)?;
+      },
+      StructPropertyValue::FluidBox(f) => self.write_f32::<E>(*f)?,
+      StructPropertyValue::DateTime(d) => self.write_i64::<E>(*d)?,
+      StructPropertyValue::FINNetworkTrace(t) => self.write_fin_network_trace::<E>(t)?,
+      StructPropertyValue::FINLuaProcessorStateStorage(s) => self.write_fin_lua_processor_state_storage::<E>(s, header)?,
+      StructPropertyValue::FICFrameRange(r) => {
+        self.write_i64::<E>(r.begin)?;
+        self.write_i64::<E>(r.end)?;
+      },
+      StructPropertyValue::Properties(properties) => self.write_properties::<E>(properties, header)?,
+      StructPropertyValue::None => {},
+    }
+    Ok(())
+  }
+
+  /// Writes a text property
+  fn write_text_property<E: ByteOrder>(&mut self, property: &TextProperty) -> Result<()> {
+    self.write_i32::<E>(property.flags)?;
+    self.write_u8(property.history_type)?;
+
+    match &property.value {
+      TextPropertyHistory::BaseHistory(h) => {
+        self.write_length_prefixed_string::<E>(&h.namespace)?;
+        self.write_length_prefixed_string::<E>(&h.key)?;
+        self.write_length_prefixed_string::<E>(&h.value)?;
+      },
+      TextPropertyHistory::ArgumentHistory(h) => {
+        self.write_text_property::<E>(&h.source_format)?;
+        self.write_i32::<E>(h.arguments.len() as i32)?;
+        for argument in &h.arguments {
+          self.write_length_prefixed_string::<E>(&argument.name)?;
+          self.write_u8(argument.value_type)?;
+          self.write_text_property::<E>(&argument.value)?;
+        }
+      },
+      TextPropertyHistory::TransformHistory(h) => {
+        self.write_text_property::<E>(&h.source_text)?;
+        self.write_u8(h.transform_type)?;
+      },
+      TextPropertyHistory::StringTableEntryHistory(h) => {
+        self.write_length_prefixed_string::<E>(&h.table_id)?;
+        self.write_length_prefixed_string::<E>(&h.text_key)?;
+      },
+      TextPropertyHistory::NoneHistory(h) => {
+        self.write_i32::<E>(h.has_culture_invariant_string)?;
+        self.write_length_prefixed_string::<E>(&h.value)?;
+      },
+      TextPropertyHistory::None => {},
+    }
+
+    Ok(())
+  }
+
+  /// Writes a property: its name and type, the size of its value (computed
+  /// by writing the value first), its index, any type-specific tag data and
+  /// finally the value itself
+  fn write_property<E: ByteOrder>(&mut self, property: &Property, header: &Header) -> Result<()> {
+    let mut value: Vec<u8> = vec![];
+    let mut tag: Vec<u8> = vec![];
+
+    match &property.value {
+      PropertyValue::Array(p) => {
+        tag.write_length_prefixed_string::<E>(&format!("{}Property", p.r#type))?;
+        tag.write_u8(0)?;
+        value.write_array_property_value::<E>(&property.name, p, header)?;
+      },
+      PropertyValue::Bool(p) => {
+        tag.write_u8(*p)?;
+        tag.write_property_guid::<E>(&property.guid)?;
+      },
+      PropertyValue::Byte(p) => {
+        tag.write_length_prefixed_string::<E>(&p.r#type)?;
+        tag.write_property_guid::<E>(&property.guid)?;
+        if p.r#type == "None" {
+          value.write_u8(p.byte_value.unwrap_or_default())?;
+        } else {
+          value.write_length_prefixed_string::<E>(p.string_value.as_deref().unwrap_or_default())?;
+        }
+      },
+      PropertyValue::Double(p) => {
+        tag.write_property_guid::<E>(&property.guid)?;
+        value.write_f64::<E>(*p)?;
+      },
+      PropertyValue::Enum(p) => {
+        let (name, enum_value) = p.iter().next().map(|(k, v)| (k.as_str(), v.as_str())).unwrap_or_default();
+        tag.write_length_prefixed_string::<E>(name)?;
+        tag.write_property_guid::<E>(&property.guid)?;
+        value.write_length_prefixed_string::<E>(enum_value)?;
+      },
+      PropertyValue::Float(p) => {
+        tag.write_property_guid::<E>(&property.guid)?;
+        value.write_f32::<E>(*p)?;
+      },
+      PropertyValue::Int(p) => {
+        tag.write_property_guid::<E>(&property.guid)?;
+        value.write_i32::<E>(*p)?;
+      },
+      PropertyValue::Int8(p) => {
+        tag.write_property_guid::<E>(&property.guid)?;
+        value.write_i8(*p)?;
+      },
+      PropertyValue::Int64(p) => {
+        tag.write_property_guid::<E>(&property.guid)?;
+        value.write_i64::<E>(*p)?;
+      },
+      PropertyValue::Map(p) => {
+        tag.write_length_prefixed_string::<E>(&format!("{}Property", p.key_type))?;
+        tag.write_length_prefixed_string::<E>(&format!("{}Property", p.value_type))?;
+        tag.write_u8(0)?;
+        value.write_map_property_value::<E>(p, header)?;
+      },
+      PropertyValue::Object(p) => {
+        tag.write_property_guid::<E>(&property.guid)?;
+        value.write_object_reference::<E>(p, &header.map_name)?;
+      },
+      PropertyValue::Set(p) => {
+        tag.write_length_prefixed_string::<E>(&format!("{}Property", p.r#type))?;
+        tag.write_u8(0)?;
+        value.write_set_property_value::<E>(p, header)?;
+      },
+      PropertyValue::String(p) => {
+        tag.write_property_guid::<E>(&property.guid)?;
+        value.write_length_prefixed_string::<E>(p)?;
+      },
+      PropertyValue::Struct((struct_type, p)) => {
+        tag.write_length_prefixed_string::<E>(struct_type)?;
+        tag.write_struct_guid(&property.struct_guid)?;
+        value.write_struct_property_value::<E>(p, header)?;
+      },
+      PropertyValue::Text(p) => {
+        tag.write_property_guid::<E>(&property.guid)?;
+        value.write_text_property::<E>(p)?;
+      },
+      PropertyValue::UInt32(p) => {
+        tag.write_property_guid::<E>(&property.guid)?;
+        value.write_u32::<E>(*p)?;
+      },
+      PropertyValue::UInt64(p) => {
+        tag.write_property_guid::<E>(&property.guid)?;
+        value.write_u64::<E>(*p)?;
+      },
+    }
+
+    self.write_length_prefixed_string::<E>(&property.name)?;
+    self.write_length_prefixed_string::<E>(&format!("{}Property", property.r#type))?;
+    self.write_i32::<E>(value.len() as i32)?;
+    self.write_i32::<E>(property.index)?;
+    self.write_all(&tag)?;
+    self.write_all(&value)?;
+
+    Ok(())
+  }
+
+  /// Writes a list of properties followed by the terminating "None" name
+  fn write_properties<E: ByteOrder>(&mut self, properties: &[Property], header: &Header) -> Result<()> {
+    for property in properties {
+      self.write_property::<E>(property, header)?;
+    }
+    self.write_length_prefixed_string::<E>("None")?;
+    Ok(())
+  }
+
+  /// Writes an object's properties and extras, preceded by its save version
+  /// and its size in bytes (computed by writing the rest first)
+  fn write_object<E: ByteOrder>(&mut self, object_header: &ObjectHeader, object: &Object, header: &Header) -> Result<()> {
+    let mut body: Vec<u8> = vec![];
+
+    let (save_version, unk_int_1, properties, extra, should_be_nulled) = match object {
+      Object::Actor(a) => {
+        body.write_object_reference::<E>(&ObjectReference {
+          level_name: a.parent_object_root.clone(),
+          path_name: a.parent_object_name.clone(),
+          level_is_map: a.parent_object_root_is_map,
+        }, &header.map_name)?;
+        body.write_i32::<E>(a.components.len() as i32)?;
+        for component in &a.components {
+          body.write_object_reference::<E>(component, &header.map_name)?;
+        }
+        (a.save_version, a.unk_int_1, &a.properties, &a.extra, a.should_be_nulled)
+      },
+      Object::Component(c) => (c.save_version, c.unk_int_1, &c.properties, &c.extra, c.should_be_nulled),
+    };
+
+    if !should_be_nulled {
+      body.write_properties::<E>(properties, header)?;
+      body.write_object_extra::<E>(object_header, extra, header)?;
+    }
+
+    self.write_i32::<E>(save_version)?;
+    self.write_i32::<E>(unk_int_1)?;
+    self.write_i32::<E>(body.len() as i32)?;
+    self.write_all(&body)?;
+
+    Ok(())
+  }
+
+  /// Writes a player state's count and ID, or the bytes they were read
+  /// from while neither has changed
+  fn write_player_state<E: ByteOrder>(&mut self, state: &PlayerState) -> Result<()> {
+    if let Some(raw) = &state.raw {
+      let read = io::Cursor::new(raw).read_player_state::<E>(raw.len() as u64).unwrap_or_default();
+      if read.count == state.count && read.id == state.id {
+        self.write_all(raw)?;
+        return Ok(());
+      }
+    }
+
+    self.write_i32::<E>(state.count)?;
+    match &state.id {
+      None => self.write_u8(3)?,
+      Some(PlayerId::Platform(id)) => {
+        self.write_u8(8)?;
+        self.write_length_prefixed_string::<E>(id)?;
+      },
+      Some(PlayerId::Epic { product_user_id, account_id }) => match from_hex(product_user_id).filter(|b| b.len() == 16) {
+        Some(bytes) if account_id.is_none() => {
+          self.write_u8(17)?;
+          self.write_u8(16)?;
+          self.write_all(&bytes)?;
+        },
+        _ => {
+          self.write_u8(248)?;
+          self.write_length_prefixed_string::<E>("")?;
+          match account_id {
+            Some(account_id) => self.write_length_prefixed_string::<E>(&format!("{product_user_id}|{account_id}"))?,
+            None => self.write_length_prefixed_string::<E>(product_user_id)?,
+          }
+        },
+      },
+      Some(PlayerId::Steam(id)) => {
+        // Read back as little endian only when that looks like a Steam ID
+        let bytes = if id >> 56 == 1 { id.to_le_bytes() } else { id.to_be_bytes() };
+        self.write_u8(241)?;
+        self.write_u8(6)?;
+        self.write_i32::<E>(bytes.len() as i32)?;
+        self.write_all(&bytes)?;
+      },
+      Some(PlayerId::Unknown { kind, bytes }) => {
+        // Kinds which the outer type byte gives a layout to are written
+        // with a length instead
+        if PLAYER_ID_LAYOUTS.contains(kind) {
+          self.write_u8(241)?;
+          self.write_u8(*kind)?;
+          self.write_i32::<E>(bytes.len() as i32)?;
+        } else {
+          self.write_u8(*kind)?;
+        }
+        self.write_all(bytes)?;
+      },
+    }
+    Ok(())
+  }
+
+  /// Writes the data following an object's properties
+  fn write_object_extra<E: ByteOrder>(&mut self, object_header: &ObjectHeader, extra: &Option<ObjectExtra>, header: &Header) -> Result<()> {
+    if let Some(ObjectHeaderType::PlayerState) = object_header.get_type() {
+      if let Some(ObjectExtra::PlayerState(e)) = extra {
+        self.write_player_state::<E>(e)?;
+      }
+      return Ok(());
+    }
+
+    match extra {
+      Some(ObjectExtra::Circuit(e)) => {
+        self.write_i32::<E>(e.count)?;
+        self.write_i32::<E>(e.elements.len() as i32)?;
+        for circuit in &e.elements {
+          self.write_i32::<E>(circuit.id)?;
+          self.write_length_prefixed_string::<E>(&circuit.level_name)?;
+          self.write_length_prefixed_string::<E>(&circuit.path_name)?;
+        }
+      },
+      Some(ObjectExtra::Conveyor(e)) => {
+        self.write_i32::<E>(e.count)?;
+        self.write_i32::<E>(e.elements.len() as i32)?;
+        for item in &e.elements {
+          self.write_i32::<E>(item.length)?;
+          self.write_length_prefixed_string::<E>(&item.name)?;
+          self.write_length_prefixed_string::<E>(&item.unk_str_1)?;
+          self.write_length_prefixed_string::<E>(&item.unk_str_2)?;
+          self.write_f32::<E>(item.position)?;
+        }
+      },
+      Some(ObjectExtra::DroneTransport(e)) => {
+        self.write_i32::<E>(e.unk_int_1)?;
+        self.write_i32::<E>(e.unk_int_2)?;
+        for actions in [&e.active_action, &e.action_queue] {
+          self.write_i32::<E>(actions.len() as i32)?;
+          for action in actions {
+            self.write_length_prefixed_string::<E>(&action.name)?;
+            self.write_properties::<E>(&action.properties, header)?;
+          }
+        }
+      },
+      Some(ObjectExtra::Game(e)) => {
+        self.write_i32::<E>(e.count)?;
+        self.write_i32::<E>(e.elements.len() as i32)?;
+        for reference in &e.elements {
+          self.write_object_reference::<E>(reference, &header.map_name)?;
+        }
+      },
+      Some(ObjectExtra::LightweightBuildables(e)) => match &e.raw {
+        Some(raw) => self.write_all(raw)?,
+        None => {
+          self.write_i32::<E>(e.count)?;
+          self.write_i32::<E>(e.classes.len() as i32)?;
+          for class in &e.classes {
+            self.write_object_reference_raw::<E>(&class.class)?;
+            self.write_i32::<E>(class.instances.len() as i32)?;
+            for instance in &class.instances {
+              self.write_quaternion_double::<E>(&instance.transform.rotation)?;
+              self.write_vector_double::<E>(&instance.transform.translation)?;
+              self.write_vector_double::<E>(&instance.transform.scale)?;
+
+              let customization = &instance.customization;
+              self.write_object_reference_raw::<E>(&customization.swatch)?;
+              self.write_object_reference_raw::<E>(&customization.material)?;
+              self.write_object_reference_raw::<E>(&customization.pattern)?;
+              self.write_object_reference_raw::<E>(&customization.skin)?;
+              self.write_color::<E>(&customization.primary_color)?;
+              self.write_color::<E>(&customization.secondary_color)?;
+              self.write_object_reference_raw::<E>(&customization.paint_finish)?;
+              self.write_u8(customization.pattern_rotation)?;
+
+              self.write_object_reference_raw::<E>(&instance.built_with_recipe)?;
+              self.write_object_reference_raw::<E>(&instance.blueprint_proxy)?;
+            }
+          }
+        },
+      },
+      Some(ObjectExtra::Locomotive(e)) => {
+        self.write_i32::<E>(e.count)?;
+        self.write_i32::<E>(e.elements.len() as i32)?;
+        for element in &e.elements {
+          self.write_length_prefixed_string::<E>(&element.name)?;
+          self.write_length_prefixed_string::<E>(&element.unk_str_1)?;
+        }
+        self.write_object_reference::<E>(&e.prev, &header.map_name)?;
+        self.write_object_reference::<E>(&e.next, &header.map_name)?;
+      },
+      Some(ObjectExtra::PowerLine(e)) => {
+        self.write_i32::<E>(e.count)?;
+        self.write_object_reference::<E>(&e.source, &header.map_name)?;
+        self.write_object_reference::<E>(&e.target, &header.map_name)?;
+      },
+      Some(ObjectExtra::Vehicle(e)) => match &e.raw {
+        Some(raw) => self.write_all(raw)?,
+        None => {
+          self.write_i32::<E>(e.count)?;
+          self.write_i32::<E>(e.elements.len() as i32)?;
+          for element in &e.elements {
+            self.write_length_prefixed_string::<E>(&element.name)?;
+            self.write_vector_double::<E>(&element.state.position)?;
+            self.write_quaternion_double::<E>(&element.state.rotation)?;
+            self.write_vector_double::<E>(&element.state.linear_velocity)?;
+            self.write_vector_double::<E>(&element.state.angular_velocity)?;
+            self.write_u8(element.state.flags)?;
+          }
+        },
+      },
+      Some(ObjectExtra::Raw(raw)) => self.write_all(raw)?,
+      Some(ObjectExtra::PlayerState(_)) | None => self.write_i32::<E>(0)?,
+    }
+
+    Ok(())
+  }
+
+  /// Writes a single level: its name (unless it's the last level, whose name
+  /// comes from the map), its object headers and collectables, its objects
+  /// and the repeated set of collectables, each group preceded by its size
+  fn write_level<E: ByteOrder>(&mut self, level: &Level, is_last_level: bool, header: &Header) -> Result<()> {
+    if !is_last_level {
+      self.write_length_prefixed_string::<E>(&level.name)?;
+    }
+
+    let mut object_headers: Vec<u8> = vec![];
+    object_headers.write_i32::<E>(level.object_headers.len() as i32)?;
+    for object_header in &level.object_headers {
+      object_headers.write_level_object_header::<E>(object_header, &header.map_name)?;
+    }
+    object_headers.write_i32::<E>(level.collectables.len() as i32)?;
+    for collectable in &level.collectables {
+      object_headers.write_object_reference::<E>(collectable, &header.map_name)?;
+    }
+    self.write_i64::<E>(object_headers.len() as i64)?;
+    self.write_all(&object_headers)?;
+
+    let mut objects: Vec<u8> = vec![];
+    objects.write_i32::<E>(level.objects.len() as i32)?;
+    for (i, object) in level.objects.iter().enumerate() {
+      let Some(object_header) = level.object_headers.get(i) else {
+        return Err(ParseError::MissingObjectHeader(level.name.clone()));
+      };
+      objects.write_object::<E>(object_header, object, header)?;
+    }
+    self.write_i64::<E>(objects.len() as i64)?;
+    self.write_all(&objects)?;
+
+    self.write_i32::<E>(level.second_collectables.len() as i32)?;
+    for collectable in &level.second_collectables {
+      self.write_object_reference::<E>(collectable, &header.map_name)?;
+    }
+
+    Ok(())
+  }
+
+  /// Writes the number of levels (less the last one) followed by each level
+  fn write_levels<E: ByteOrder>(&mut self, levels: &[Level], header: &Header) -> Result<()> {
+    if levels.is_empty() {
+      return Err(ParseError::MissingLevel);
+    }
+
+    self.write_i32::<E>(levels.len() as i32 - 1)?;
+    for (i, level) in levels.iter().enumerate() {
+      self.write_level::<E>(level, i == levels.len() - 1, header)?;
+    }
+    Ok(())
+  }
+}
+
+/// Auto-implements the above trait for all types which implement `io::Write`
+impl<W: io::Write> WriteSaveFileBytes for W {}
+
+impl PlayerState {
+  /// The bytes `write_player_state` writes for the count and ID
+  pub(crate) fn to_bytes<E: ByteOrder>(&self) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    bytes.write_player_state::<E>(&PlayerState { raw: None, ..self.clone() })?;
+    Ok(bytes)
+  }
+}
+
+impl Save {
+  /// Writes the whole save, i.e. the header followed by the partitions,
+  /// levels and any trailing bytes compressed into chunks, the reverse of `read_file`
+  pub fn write<W: io::Write>(&self, writer: &mut W) -> Result<()> {
+    writer.write_header::<LittleEndian>(&self.header)?;
+
+    let mut body: Vec<u8> = vec![];
+    body.write_partitions::<LittleEndian>(&self.partitions)?;
+    body.write_levels::<LittleEndian>(&self.levels, &self.header)?;
+    body.write_all(&self.trailing)?;
+
+    // The body starts with the size of the rest of the body
+    let mut body_bytes: Vec<u8> = Vec::with_capacity(body.len() + 8);
+    body_bytes.write_i64::<LittleEndian>(body.len() as i64)?;
+    body_bytes.extend(body);
+
+    for chunk in body_bytes.chunks(MAX_CHUNK_SIZE) {
+      writer.write_chunk::<LittleEndian>(chunk)?;
+    }
+
+    Ok(())
+  }
+
+  /// Writes the whole save to the file at the given path
+  pub fn write_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+    let mut writer = io::BufWriter::new(fs::File::create(path)?);
+    self.write(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+  }
+}
@@ -0,0 +1,38 @@
+use std::collections::BTreeMap;
+
+use convey_rs::collectibles::*;
+use convey_rs::save::*;
+
+#[test]
+fn catalogues_every_item_of_a_listed_kind() {
+  let catalogue = CollectibleCatalogue::embedded();
+  assert!(!catalogue.totals.is_empty());
+
+  let mut counts: BTreeMap<CollectibleKind, usize> = BTreeMap::new();
+  for item in &catalogue.items {
+    *counts.entry(item.kind).or_default() += 1;
+  }
+  for (kind, count) in counts {
+    assert_eq!(Some(&count), catalogue.totals.get(&kind), "{kind:?}");
+  }
+}
+
+#[test]
+fn finds_unopened_drop_pods_near_a_point() {
+  let pod = |name: &str, x: f32, is_opened: bool| DropPod {
+    instance_name: format!("Persistent_Level:PersistentLevel.{name}"),
+    position: Vector { x, y: 0.0, z: 0.0 },
+    is_opened,
+    is_repaired: false,
+    repair: None,
+  };
+  let collectibles = Collectibles {
+    drop_pods: vec![pod("BP_DropPod1", 100.0, false), pod("BP_DropPod2", 50.0, true), pod("BP_DropPod3", 5000.0, false)],
+    ..Default::default()
+  };
+
+  let catalogue = CollectibleCatalogue::embedded().with_drop_pods(&collectibles);
+  let near = collectibles.remaining_near(&catalogue, &Vector { x: 0.0, y: 0.0, z: 0.0 }, 1000.0);
+  let names: Vec<&str> = near.iter().map(|(item, _)| item.path_name.as_str()).collect();
+  assert_eq!(names, ["Persistent_Level:PersistentLevel.BP_DropPod1"]);
+}
@@ -0,0 +1,88 @@
+use convey_rs::errors::ParseError;
+use convey_rs::save::*;
+
+const STEAM_ID: u64 = 76_561_197_960_287_930;
+
+fn product_user_id() -> Vec<u8> {
+  (0x10..0x20).collect()
+}
+
+fn account_id() -> Vec<u8> {
+  (0xa0..0xb0).collect()
+}
+
+#[test]
+fn reads_steam_ids_in_either_byte_order() {
+  assert_eq!(PlayerId::from_steam_bytes(6, &STEAM_ID.to_le_bytes()), PlayerId::Steam(STEAM_ID));
+  assert_eq!(PlayerId::from_steam_bytes(6, &STEAM_ID.to_be_bytes()), PlayerId::Steam(STEAM_ID));
+}
+
+#[test]
+fn keeps_steam_ids_of_the_wrong_length() {
+  let bytes = [1, 2, 3];
+  assert_eq!(PlayerId::from_steam_bytes(25, &bytes), PlayerId::Unknown { kind: 25, bytes: bytes.to_vec() });
+}
+
+#[test]
+fn reads_a_bare_epic_id() {
+  assert_eq!(PlayerId::from_epic_bytes(17, &product_user_id()), PlayerId::Epic {
+    product_user_id: "101112131415161718191a1b1c1d1e1f".to_string(),
+    account_id: None,
+  });
+}
+
+#[test]
+fn reads_a_padded_epic_id() {
+  let mut bytes = vec![0, 0, 1];
+  bytes.extend(product_user_id());
+  assert_eq!(PlayerId::from_epic_bytes(1, &bytes), PlayerId::Epic {
+    product_user_id: "101112131415161718191a1b1c1d1e1f".to_string(),
+    account_id: None,
+  });
+
+  bytes.extend(account_id());
+  assert_eq!(PlayerId::from_epic_bytes(1, &bytes), PlayerId::Epic {
+    product_user_id: "101112131415161718191a1b1c1d1e1f".to_string(),
+    account_id: Some("a0a1a2a3a4a5a6a7a8a9aaabacadaeaf".to_string()),
+  });
+}
+
+#[test]
+fn keeps_epic_ids_too_short_to_read() {
+  for bytes in [vec![], vec![0; 8], vec![1, 2, 3]] {
+    assert_eq!(PlayerId::from_epic_bytes(1, &bytes), PlayerId::Unknown { kind: 1, bytes });
+  }
+}
+
+#[test]
+fn parses_what_it_displays() {
+  let ids = [
+    PlayerId::Steam(STEAM_ID),
+    PlayerId::Epic { product_user_id: "101112131415161718191a1b1c1d1e1f".to_string(), account_id: None },
+    PlayerId::Epic {
+      product_user_id: "101112131415161718191a1b1c1d1e1f".to_string(),
+      account_id: Some("a0a1a2a3a4a5a6a7a8a9aaabacadaeaf".to_string()),
+    },
+    PlayerId::Platform("PS5-1234".to_string()),
+    PlayerId::Unknown { kind: 42, bytes: vec![0, 1, 254, 255] },
+  ];
+
+  for id in ids {
+    assert_eq!(id.to_string().parse::<PlayerId>().unwrap(), id);
+  }
+}
+
+#[test]
+fn lowercases_parsed_epic_ids() {
+  assert_eq!("epic:ABCDEF|0A".parse::<PlayerId>().unwrap(), PlayerId::Epic {
+    product_user_id: "abcdef".to_string(),
+    account_id: Some("0a".to_string()),
+  });
+}
+
+#[test]
+fn rejects_malformed_ids() {
+  for id in ["", "steam", "steam:abc", "epic:", "unknown:1", "unknown:1:abc", "unknown:x:00", "xbox:123"] {
+    assert!(matches!(id.parse::<PlayerId>(), Err(ParseError::InvalidPlayerId(_))), "{id}");
+  }
+}
@@ -0,0 +1,58 @@
+use convey_rs::buildings::*;
+use convey_rs::production::*;
+use convey_rs::save::*;
+
+fn manufacturer(kind: ManufacturerKind, recipe: &str, current_potential: f32, current_production_boost: f32) -> Manufacturer {
+  Manufacturer {
+    instance_name: "Persistent_Level:PersistentLevel.Build_OilRefinery_C_1".to_string(),
+    kind,
+    position: Vector { x: 0.0, y: 0.0, z: 0.0 },
+    recipe: Some(format!("/Game/FactoryGame/Recipes/{recipe}.{recipe}_C")),
+    current_potential,
+    pending_potential: current_potential,
+    current_production_boost,
+    pending_production_boost: current_production_boost,
+    power_shards: 0,
+    somersloops: 0,
+    productivity: 1.0,
+    manufacturing_progress: 0.0,
+    is_production_paused: false,
+    input_inventory: vec![],
+    output_inventory: vec![],
+  }
+}
+
+fn rate(report: &ProductionReport, item: &str) -> (f64, f64) {
+  let rate = &report.items[item];
+  (rate.produced, rate.consumed)
+}
+
+#[test]
+fn computes_the_rates_of_an_alternate_recipe() {
+  let refinery = manufacturer(ManufacturerKind::Refinery, "Recipe_Alternate_PureIronIngot", 1.0, 1.0);
+  let report = production_report([&refinery], &RecipeDatabase::embedded());
+
+  assert!(report.unknown_recipes.is_empty());
+  assert_eq!(rate(&report, "Desc_OreIron_C"), (0.0, 35.0));
+  assert_eq!(rate(&report, "Desc_Water_C"), (0.0, 20.0));
+  assert_eq!(rate(&report, "Desc_IronIngot_C"), (65.0, 0.0));
+}
+
+#[test]
+fn scales_inputs_by_clock_and_outputs_by_boost() {
+  let encoder = manufacturer(ManufacturerKind::QuantumEncoder, "Recipe_SuperpositionOscillator", 0.5, 2.0);
+  assert_eq!((encoder.input_rate_multiplier(), encoder.output_rate_multiplier()), (0.5, 1.0));
+
+  let report = production_report([&encoder], &RecipeDatabase::embedded());
+
+  assert_eq!(rate(&report, "Desc_DarkMatter_C"), (0.0, 15.0));
+  assert_eq!(rate(&report, "Desc_QuantumOscillator_C"), (5.0, 0.0));
+  assert_eq!(rate(&report, "Desc_DarkEnergy_C"), (125.0, 0.0));
+}
+
+#[test]
+fn reports_recipes_it_doesnt_know() {
+  let constructor = manufacturer(ManufacturerKind::Constructor, "Recipe_Unknown", 1.0, 1.0);
+  let report = production_report([&constructor], &RecipeDatabase::embedded());
+  assert!(report.unknown_recipes.contains("Recipe_Unknown_C"));
+}
@@ -0,0 +1,73 @@
+use convey_rs::errors::ParseError;
+use convey_rs::region::*;
+use convey_rs::save::*;
+
+const FOUNDATION: &str = "/Game/FactoryGame/Buildable/Building/Foundation/Build_Foundation_8x4_01.Build_Foundation_8x4_01_C";
+
+fn instance(x: f64) -> LightweightInstance {
+  LightweightInstance {
+    transform: Transform {
+      translation: Vector { x, y: 0.0, z: 0.0 },
+      ..Default::default()
+    },
+    ..Default::default()
+  }
+}
+
+/// A save holding only a lightweight buildable subsystem with the given
+/// foundations
+fn save(foundations: Vec<LightweightInstance>) -> Save {
+  let mut lightweight = LightweightBuildables::default();
+  for foundation in foundations {
+    lightweight.add(FOUNDATION, foundation);
+  }
+
+  Save {
+    levels: vec![Level {
+      object_headers: vec![ObjectHeader::Actor(ActorHeader {
+        type_path: "/Script/FactoryGame.FGLightweightBuildableSubsystem".to_string(),
+        instance_name: "Persistent_Level:PersistentLevel.LightweightBuildableSubsystem".to_string(),
+        ..Default::default()
+      })],
+      objects: vec![Object::Actor(ActorObject {
+        extra: Some(ObjectExtra::LightweightBuildables(lightweight)),
+        ..Default::default()
+      })],
+      ..Default::default()
+    }],
+    ..Default::default()
+  }
+}
+
+fn region() -> Region {
+  Region::Box {
+    min: Vector { x: -100.0, y: -100.0, z: -100.0 },
+    max: Vector { x: 100.0, y: 100.0, z: 100.0 },
+  }
+}
+
+#[test]
+fn copies_lightweight_buildables_inside_the_region() {
+  let source = save(vec![instance(50.0), instance(5000.0)]);
+  let mut target = save(vec![instance(0.0)]);
+
+  copy_region(&source, &region(), &mut target, &Vector { x: 800.0, y: 0.0, z: 0.0 }).unwrap();
+
+  let positions: Vec<f64> = target.lightweight_buildables().unwrap()
+    .instances(FOUNDATION)
+    .map(|i| i.transform.translation.x)
+    .collect();
+  assert_eq!(positions, [0.0, 850.0]);
+}
+
+#[test]
+fn refuses_to_copy_lightweight_buildables_without_a_subsystem() {
+  let source = save(vec![instance(50.0)]);
+  let mut target = Save {
+    levels: vec![Level::default()],
+    ..Default::default()
+  };
+
+  let result = copy_region(&source, &region(), &mut target, &Vector::default());
+  assert!(matches!(result, Err(ParseError::MissingObject(_))));
+}
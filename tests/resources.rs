@@ -0,0 +1,62 @@
+use convey_rs::property::*;
+use convey_rs::resources::*;
+use convey_rs::save::*;
+
+const NODE: &str = "Persistent_Level:PersistentLevel.BP_ResourceNode42";
+
+fn catalogue() -> NodeCatalogue {
+  NodeCatalogue::from_json(&format!(r#"[{{
+    "path_name": "{NODE}",
+    "kind": "Node",
+    "resource": "/Game/FactoryGame/Resource/RawResources/OreIron/Desc_OreIron.Desc_OreIron_C",
+    "purity": "Pure",
+    "position": {{ "x": 1000.0, "y": 0.0, "z": 0.0 }}
+  }}]"#)).unwrap()
+}
+
+fn save(properties: Vec<Property>) -> Save {
+  Save {
+    levels: vec![Level {
+      object_headers: vec![ObjectHeader::Actor(ActorHeader {
+        type_path: "/Game/FactoryGame/Buildable/Factory/MinerMk2/Build_MinerMk2.Build_MinerMk2_C".to_string(),
+        instance_name: "Persistent_Level:PersistentLevel.Build_MinerMk2_C_1".to_string(),
+        ..Default::default()
+      })],
+      objects: vec![Object::Actor(ActorObject { properties, ..Default::default() })],
+      ..Default::default()
+    }],
+    ..Default::default()
+  }
+}
+
+#[test]
+fn parses_the_embedded_catalogue() {
+  let nodes = NodeCatalogue::embedded().nodes;
+  assert!(nodes.iter().all(|n| ResourceNodeKind::from_name(&n.path_name).is_some()));
+}
+
+#[test]
+fn joins_extractors_to_catalogued_nodes() {
+  let resources = save(vec![
+    Property::new("mExtractableResource", PropertyValue::Object(ObjectReference {
+      level_name: "Persistent_Level".to_string(),
+      path_name: NODE.to_string(),
+      level_is_map: false,
+    })),
+    Property::new("mCurrentPotential", PropertyValue::Float(1.5)),
+  ]).resources(&catalogue());
+
+  let node = resources.node(NODE).unwrap();
+  assert_eq!(node.purity, Some(Purity::Pure));
+  let extractor = node.extractor.as_ref().unwrap();
+  assert_eq!((extractor.kind, extractor.tier, extractor.clock), (ExtractorKind::Miner, Some(2), 1.5));
+  assert_eq!(resources.unexploited().count(), 0);
+}
+
+#[test]
+fn finds_unexploited_nodes_near_a_point() {
+  let resources = save(vec![]).resources(&catalogue());
+  let origin = Vector { x: 0.0, y: 0.0, z: 0.0 };
+  assert_eq!(resources.unexploited_near(&origin, 2000.0).len(), 1);
+  assert!(resources.unexploited_near(&origin, 500.0).is_empty());
+}
@@ -0,0 +1,286 @@
+use std::env;
+use std::f32::consts::FRAC_1_SQRT_2;
+use std::fs;
+use std::path::PathBuf;
+
+use convey_rs::property::*;
+use convey_rs::save::*;
+
+const MAP_NAME: &str = "Persistent_Level";
+
+/// Path of a real save to round-trip with `cargo test -- --ignored`
+const TEST_SAVE_VAR: &str = "CONVEY_RS_TEST_SAVE";
+
+fn temp_path(name: &str) -> PathBuf {
+  env::temp_dir().join(format!("convey-rs-{}-{name}.sav", std::process::id()))
+}
+
+/// Writes a save and reads it back
+fn write_and_read(save: &Save, name: &str) -> (Vec<u8>, Save) {
+  let path = temp_path(name);
+  save.write_file(&path).unwrap();
+  let bytes = fs::read(&path).unwrap();
+  let read = convey_rs::read_file(&path).unwrap();
+  fs::remove_file(&path).unwrap();
+  (bytes, read)
+}
+
+fn assert_same(a: &Save, b: &Save) {
+  assert_eq!(serde_json::to_value(a).unwrap(), serde_json::to_value(b).unwrap());
+}
+
+fn reference(level_name: &str, path_name: &str) -> ObjectReference {
+  ObjectReference {
+    level_name: level_name.to_string(),
+    path_name: path_name.to_string(),
+    level_is_map: false,
+  }
+}
+
+fn map_reference(path_name: &str) -> ObjectReference {
+  ObjectReference {
+    level_is_map: true,
+    ..reference("", path_name)
+  }
+}
+
+fn actor(type_path: &str, instance_name: &str) -> ActorHeader {
+  ActorHeader {
+    type_path: type_path.to_string(),
+    instance_name: instance_name.to_string(),
+    needs_transform: 1,
+    rotation: Quaternion { x: 0.0, y: 0.0, z: FRAC_1_SQRT_2, w: FRAC_1_SQRT_2 },
+    position: Vector { x: 1200.0, y: -800.0, z: 100.0 },
+    scale: Vector { x: 1.0, y: 1.0, z: 1.0 },
+    was_placed_in_level: 0,
+    ..Default::default()
+  }
+}
+
+fn actor_object(properties: Vec<Property>, extra: Option<ObjectExtra>) -> ActorObject {
+  ActorObject {
+    save_version: 46,
+    unk_int_1: 1,
+    parent_object_root_is_map: true,
+    properties,
+    extra,
+    ..Default::default()
+  }
+}
+
+fn save() -> Save {
+  let mut location = Property::new("mLocation", PropertyValue::Struct((
+    "Vector".to_string(),
+    StructPropertyValue::DoubleVector(Vector { x: 1.5, y: -2.25, z: 3.0 }),
+  )));
+  location.struct_guid = Some((1..=17).collect());
+
+  let foundation = "/Game/FactoryGame/Buildable/Building/Foundation/Build_Foundation_8x4_01.Build_Foundation_8x4_01_C";
+  let belt = "/Game/FactoryGame/Buildable/Factory/ConveyorBeltMk1/Build_ConveyorBeltMk1.Build_ConveyorBeltMk1_C";
+  let power_line = "/Game/FactoryGame/Buildable/Factory/PowerLine/Build_PowerLine.Build_PowerLine_C";
+  let subsystem = "/BuildGunUtilities/BGU_Subsystem.BGU_Subsystem_C";
+
+  let persistent_level = Level {
+    name: format!("Level {MAP_NAME}"),
+    object_headers: vec![
+      ObjectHeader::Actor(actor(belt, "Persistent_Level:PersistentLevel.Build_ConveyorBeltMk1_C_1")),
+      ObjectHeader::Component(ComponentHeader {
+        type_path: "/Script/FactoryGame.FGFactoryConnectionComponent".to_string(),
+        instance_name: "Persistent_Level:PersistentLevel.Build_ConveyorBeltMk1_C_1.ConveyorAny0".to_string(),
+        parent_actor_name: "Persistent_Level:PersistentLevel.Build_ConveyorBeltMk1_C_1".to_string(),
+        ..Default::default()
+      }),
+      ObjectHeader::Actor(actor(power_line, "Persistent_Level:PersistentLevel.Build_PowerLine_C_1")),
+      ObjectHeader::Actor(actor(subsystem, "Persistent_Level:PersistentLevel.BGU_Subsystem_C_1")),
+      ObjectHeader::Actor(actor("/Script/FactoryGame.FGWorldSettings", "Persistent_Level:PersistentLevel.FGWorldSettings")),
+    ],
+    collectables: vec![map_reference("Persistent_Level:PersistentLevel.BP_Crystal_1")],
+    objects: vec![
+      Object::Actor(ActorObject {
+        components: vec![map_reference("Persistent_Level:PersistentLevel.Build_ConveyorBeltMk1_C_1.ConveyorAny0")],
+        ..actor_object(vec![
+          Property::new("mLength", PropertyValue::Float(812.5)),
+          location,
+        ], Some(ObjectExtra::Conveyor(Extra {
+          count: 0,
+          elements: vec![Conveyor {
+            length: 0,
+            name: "/Game/FactoryGame/Resource/Parts/IronPlate/Desc_IronPlate.Desc_IronPlate_C".to_string(),
+            unk_str_1: String::new(),
+            unk_str_2: "Persistent_Level:PersistentLevel.Build_ConveyorBeltMk1_C_1.Item".to_string(),
+            position: 120.0,
+          }],
+        })))
+      }),
+      Object::Component(ComponentObject {
+        save_version: 46,
+        properties: vec![
+          Property::new("mConnectedComponent", PropertyValue::Object(map_reference("Persistent_Level:PersistentLevel.Build_ConveyorBeltMk1_C_1.ConveyorAny1"))),
+        ],
+        ..Default::default()
+      }),
+      Object::Actor(actor_object(vec![], Some(ObjectExtra::PowerLine(PowerLine {
+        count: 0,
+        source: map_reference("Persistent_Level:PersistentLevel.Build_PowerPoleMk1_C_1.PowerConnection"),
+        target: map_reference("Persistent_Level:PersistentLevel.Build_PowerPoleMk1_C_2.PowerConnection"),
+      })))),
+      Object::Actor(actor_object(vec![
+        Property::new("mBuildings", PropertyValue::Map(MapProperty {
+          key_type: "Struct".to_string(),
+          value_type: "Object".to_string(),
+          unk_float_1: Some(Numeric::Float(1.0)),
+          unk_float_2: Some(Numeric::Float(2.0)),
+          unk_float_3: Some(Numeric::Float(3.0)),
+          unk_float_4: Some(Numeric::Float(4.0)),
+          unk_str_1: Some("Persistent_Level:PersistentLevel.Build_Foundation_8x4_01_C_1".to_string()),
+          keys: vec![MapPropertyKey::FloatVector(Vector { x: 1.0, y: 2.0, z: 3.0 })],
+          ..Default::default()
+        })),
+      ], Some(ObjectExtra::Raw(vec![0; 4])))),
+      Object::Actor(actor_object(vec![
+        Property::new("mIcon", PropertyValue::Object(reference("", "/Game/FactoryGame/Interface/UI/Assets/Icons/Icon_Factory.Icon_Factory"))),
+        Property::new("mNames", PropertyValue::Array(ArrayProperty {
+          r#type: "Str".to_string(),
+          elements: vec![ArrayPropertyValue::Str("Assembly".to_string()), ArrayPropertyValue::Str("Smelting".to_string())],
+          ..Default::default()
+        })),
+        Property::new("mFogOfWarRawData", PropertyValue::Array(ArrayProperty {
+          r#type: "Byte".to_string(),
+          elements: vec![ArrayPropertyValue::Bytes(vec![0, 0, 255, 255, 0, 0, 0, 255])],
+          ..Default::default()
+        })),
+      ], Some(ObjectExtra::Raw(vec![0, 0, 0, 0, 1, 2, 3, 4])))),
+    ],
+    second_collectables: vec![map_reference("Persistent_Level:PersistentLevel.BP_Crystal_1")],
+  };
+
+  let sub_level = Level {
+    name: "Level_FFFF_0000".to_string(),
+    object_headers: vec![ObjectHeader::Actor(ActorHeader {
+      root_object: Some("Level_FFFF_0000".to_string()),
+      ..actor(foundation, "Level_FFFF_0000:PersistentLevel.Build_Foundation_8x4_01_C_1")
+    })],
+    collectables: vec![],
+    objects: vec![Object::Actor(actor_object(vec![
+      Property::new("mBuiltWithRecipe", PropertyValue::Object(reference("", "/Game/FactoryGame/Recipes/Buildings/Foundations/Recipe_Foundation_8x4_01.Recipe_Foundation_8x4_01_C"))),
+      Property::new("mIsUsed", PropertyValue::Bool(1)),
+    ], None))],
+    second_collectables: vec![],
+  };
+
+  Save {
+    header: Header {
+      save_header_version: 13,
+      save_file_version: 46,
+      build_version: 365306,
+      map_name: MAP_NAME.to_string(),
+      map_options: "?startloc=Grass Fields?sessionName=Round trip".to_string(),
+      session_name: "Round trip".to_string(),
+      played_seconds: 3600,
+      save_timestamp: 638_500_000_000_000_000,
+      session_visibility: 0,
+      editor_object_version: 1,
+      mod_metadata: String::new(),
+      mod_flags: 0,
+      save_identifier: "ZPqJ1ZwKZkWZm4yqkQH8Gw".to_string(),
+      is_partitioned_world: 1,
+      saved_data_hash: "0123456789".to_string(),
+      is_creative_mode_enabled: 0,
+    },
+    partitions: Partitions {
+      partitions: vec![
+        Partition {
+          name: "MainGrid".to_string(),
+          cell_size: 51200,
+          hash: 3_164_127_185,
+          levels: vec![("Level_FFFF_0000".to_string(), 1_024_322_812)],
+        },
+        Partition {
+          name: "LandscapeGrid".to_string(),
+          cell_size: 102400,
+          hash: 2_097_344_019,
+          levels: vec![],
+        },
+      ],
+    },
+    levels: vec![sub_level, persistent_level],
+    trailing: vec![0, 0, 0, 0],
+  }
+}
+
+#[test]
+fn round_trips_a_written_save() {
+  let (first_bytes, first) = write_and_read(&save(), "written-1");
+  let (second_bytes, second) = write_and_read(&first, "written-2");
+
+  assert_same(&first, &second);
+  assert_eq!(first_bytes, second_bytes);
+
+  let belt = &first.levels[1].objects[0];
+  match belt.get_extra() {
+    Some(ObjectExtra::Conveyor(e)) => assert_eq!(e.elements[0].unk_str_2, "Persistent_Level:PersistentLevel.Build_ConveyorBeltMk1_C_1.Item"),
+    extra => panic!("Expected a conveyor extra, got {extra:?}"),
+  }
+  assert_eq!(belt.get_properties()[1].struct_guid, Some((1..=17).collect()));
+
+  match &first.levels[1].objects[3].get_properties()[0].value {
+    PropertyValue::Map(m) => {
+      assert_eq!((m.keys.len(), m.values.len()), (1, 0));
+      assert_eq!(m.unk_str_1.as_deref(), Some("Persistent_Level:PersistentLevel.Build_Foundation_8x4_01_C_1"));
+    },
+    value => panic!("Expected a map, got {value:?}"),
+  }
+
+  match &first.levels[1].objects[4].get_properties()[2].value {
+    PropertyValue::Array(a) => assert_eq!(a.num_elements(), 8),
+    value => panic!("Expected an array, got {value:?}"),
+  }
+
+  assert_eq!(first.partitions.partitions.len(), 2);
+  assert_eq!(first.trailing, [0, 0, 0, 0]);
+  assert!(matches!(first.levels[0].object_headers[0], ObjectHeader::Actor(ActorHeader { root_object: Some(_), .. })));
+}
+
+#[test]
+#[ignore = "needs a real save, set CONVEY_RS_TEST_SAVE and run with --ignored"]
+fn round_trips_a_real_save() {
+  let path = env::var(TEST_SAVE_VAR)
+    .unwrap_or_else(|_| panic!("Set {TEST_SAVE_VAR} to the path of a save to run this"));
+
+  let read = convey_rs::read_file(path).unwrap();
+  let (first_bytes, first) = write_and_read(&read, "real-1");
+  let (second_bytes, second) = write_and_read(&first, "real-2");
+
+  assert_same(&read, &first);
+  assert_same(&first, &second);
+  assert_eq!(first_bytes, second_bytes);
+}
+
+#[test]
+fn writes_an_edited_player_id() {
+  let player_state = "/Game/FactoryGame/Character/Player/BP_PlayerState.BP_PlayerState_C";
+  let mut save = save();
+  let level = &mut save.levels[1];
+  level.object_headers.push(ObjectHeader::Actor(actor(player_state, "Persistent_Level:PersistentLevel.BP_PlayerState_C_1")));
+  level.objects.push(Object::Actor(actor_object(vec![], Some(ObjectExtra::PlayerState(PlayerState {
+    count: 1,
+    id: Some(PlayerId::Steam(76_561_197_960_287_930)),
+    raw: None,
+  })))));
+
+  let player_id = |save: &Save| match save.levels[1].objects.last().and_then(|o| o.get_extra()) {
+    Some(ObjectExtra::PlayerState(e)) => e.id.clone(),
+    extra => panic!("Expected a player state, got {extra:?}"),
+  };
+
+  let (_, mut first) = write_and_read(&save, "player-1");
+  assert_eq!(player_id(&first), Some(PlayerId::Steam(76_561_197_960_287_930)));
+
+  let epic = PlayerId::Epic { product_user_id: "00112233445566778899aabbccddeeff".to_string(), account_id: None };
+  match first.levels[1].objects.last_mut() {
+    Some(Object::Actor(ActorObject { extra: Some(ObjectExtra::PlayerState(e)), .. })) => e.id = Some(epic.clone()),
+    object => panic!("Expected a player state, got {object:?}"),
+  }
+  let (_, second) = write_and_read(&first, "player-2");
+  assert_eq!(player_id(&second), Some(epic));
+}